@@ -0,0 +1,132 @@
+//! Charts drawn with Unicode block characters, for the `--chart` options.
+
+use chrono::{Datelike, NaiveDate};
+use colored::*;
+
+use crate::Money;
+
+/// Ends of bars, from one to seven eighths of a cell.
+const BAR_EIGHTHS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+const SPARKLINE_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const HEAT_MAP_LEVELS: [&str; 5] = ["··", "░░", "▒▒", "▓▓", "██"];
+
+/// Width of the terminal, 80 columns when it is unknown, e.g. when the output
+/// is piped.
+pub fn terminal_width() -> usize {
+    terminal_size::terminal_size()
+        .map(|(terminal_size::Width(width), _)| usize::from(width))
+        .unwrap_or(80)
+}
+
+/// A horizontal bar `width` cells long for `max`, down to an eighth of a
+/// cell.
+pub fn bar(value: i64, max: i64, width: usize) -> String {
+    if value <= 0 || max <= 0 {
+        return String::new();
+    }
+
+    let eighths = (value as f64 / max as f64 * (width * 8) as f64).round() as usize;
+    let (cells, rest) = (eighths / 8, eighths % 8);
+    let mut bar = "█".repeat(cells);
+    if rest > 0 {
+        bar.push(BAR_EIGHTHS[rest - 1]);
+    }
+
+    bar
+}
+
+/// A line of blocks as high as the values, from the lowest to the highest,
+/// at most `width` characters long.
+///
+/// Longer series are resampled, keeping the last value of each character.
+pub fn sparkline(values: &[i64], width: usize) -> String {
+    let values = if values.len() > width {
+        (1..=width)
+            .map(|i| values[i * values.len() / width - 1])
+            .collect::<Vec<_>>()
+    } else {
+        values.to_vec()
+    };
+    let min = values.iter().copied().min().unwrap_or(0);
+    let max = values.iter().copied().max().unwrap_or(0);
+
+    values
+        .iter()
+        .map(|value| {
+            if max == min {
+                SPARKLINE_LEVELS[3]
+            } else {
+                let level = (value - min) as f64 / (max - min) as f64 * 7.0;
+                SPARKLINE_LEVELS[level.round() as usize]
+            }
+        })
+        .collect()
+}
+
+/// Prints a sparkline of balances over time, between its first and last
+/// dates, followed by its lowest and highest balances.
+pub fn print_balances_sparkline(balances: &[(NaiveDate, i64)]) {
+    let (first, last) = match (balances.first(), balances.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return,
+    };
+    let values = balances.iter().map(|(_, value)| *value).collect::<Vec<_>>();
+    let width = terminal_width().saturating_sub(2 * 11 + 2).max(10);
+
+    let line = sparkline(&values, width);
+    println!(
+        "{} {} {}",
+        first.0,
+        if last.1 >= first.1 {
+            line.green()
+        } else {
+            line.red()
+        },
+        last.0
+    );
+    println!(
+        "lowest {}  highest {}",
+        Money(values.iter().copied().min().unwrap_or(0)),
+        Money(values.iter().copied().max().unwrap_or(0))
+    );
+}
+
+/// A month calendar of daily values, one line per week from Monday to
+/// Sunday, each day shaded from the lowest to the highest value.
+pub fn calendar_heat_map(days: &[(NaiveDate, i64)]) -> Vec<String> {
+    let max = days.iter().map(|(_, value)| *value).max().unwrap_or(0);
+    let mut lines = vec!["Mo Tu We Th Fr Sa Su".to_string()];
+
+    let mut line = match days.first() {
+        Some((date, _)) => "   ".repeat(date.weekday().num_days_from_monday() as usize),
+        None => return lines,
+    };
+    for (date, value) in days {
+        let level = if *value <= 0 || max <= 0 {
+            0
+        } else {
+            ((*value as f64 / max as f64) * 4.0).ceil() as usize
+        };
+        let cell = HEAT_MAP_LEVELS[level];
+        line.push_str(&if level == 0 {
+            cell.dimmed().to_string()
+        } else {
+            cell.red().to_string()
+        });
+        line.push(' ');
+
+        if date.weekday() == chrono::Weekday::Sun {
+            lines.push(line.trim_end().to_string());
+            line = String::new();
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line.trim_end().to_string());
+    }
+    lines.push(format!(
+        "less {} more",
+        HEAT_MAP_LEVELS[1..].join(" ").red()
+    ));
+
+    lines
+}
@@ -5,12 +5,13 @@ use rufm_core::{
     TransactionsRepository,
 };
 
-use crate::{handlers::Handler, AccountsShowOpt, Money};
+use crate::{charts, handlers::Handler, AccountsShowOpt, Money};
 
 impl Handler for AccountsShowOpt {
     fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
         let account = client.get_account_by_name(&self.name)?;
         let balance = client.get_account_balance(&account.id)?;
+        let projected_balance = client.get_account_projected_balance(&account.id)?;
 
         struct TransactionData {
             transaction: Transaction,
@@ -30,6 +31,24 @@ impl Handler for AccountsShowOpt {
             .collect::<QueryResult<Vec<_>>>()?;
 
         println!("{:40} {:6}", account.name, Money(balance));
+        if projected_balance != balance {
+            println!("{:40} {:6}", "  projected", Money(projected_balance));
+        }
+        if self.chart {
+            if let Some(first_transaction) = transactions_data.last() {
+                let history = client.get_account_balance_history(
+                    &account.id,
+                    &first_transaction.transaction.date,
+                    &chrono::Local::now().naive_local().date(),
+                )?;
+                charts::print_balances_sparkline(
+                    &history
+                        .iter()
+                        .map(|daily_balance| (daily_balance.date, daily_balance.balance))
+                        .collect::<Vec<_>>(),
+                );
+            }
+        }
         println!(" -- ");
         for TransactionData {
             transaction,
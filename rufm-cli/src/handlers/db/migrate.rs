@@ -0,0 +1,19 @@
+use crate::{handlers::Handler, DbMigrateOpt};
+
+impl Handler for DbMigrateOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        let report = client.migrate()?;
+
+        if let Some(backup_path) = &report.backup_path {
+            println!("Backed up the database to {}", backup_path);
+        }
+        for version in &report.migrations {
+            println!("Applied migration {}", version);
+        }
+        if report.migrations.is_empty() {
+            println!("The database is up to date");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use crate::{handlers::Handler, DbCommand, DbMigrateOpt, DbStatusOpt};
+
+mod migrate;
+mod status;
+
+impl Handler for DbCommand {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            DbCommand::Status => DbStatusOpt.handle(client),
+            DbCommand::Migrate => DbMigrateOpt.handle(client),
+        }
+    }
+}
+
+/// Creates the schema of a new database, and refuses to work on one which
+/// is out of date, which only `rufm db migrate` changes.
+pub fn prepare_schema(client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+    let status = client.migration_status()?;
+    if status.is_empty() {
+        client.migrate()?;
+    } else if !status.pending.is_empty() {
+        return Err(format!(
+            "The database has {} pending migrations, run `rufm db migrate` to apply them",
+            status.pending.len()
+        )
+        .into());
+    }
+
+    Ok(())
+}
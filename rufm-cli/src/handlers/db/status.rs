@@ -0,0 +1,29 @@
+use colored::*;
+
+use crate::{handlers::Handler, DbStatusOpt};
+
+impl Handler for DbStatusOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        let status = client.migration_status()?;
+
+        for version in &status.applied {
+            println!("{} {}", version, "applied".green());
+        }
+        for version in &status.pending {
+            println!("{} {}", version, "pending".yellow());
+        }
+        for version in &status.unknown {
+            println!("{} {}", version, "unknown".red());
+        }
+
+        if !status.unknown.is_empty() {
+            println!("The database was migrated by a newer version of rufm");
+        } else if !status.pending.is_empty() {
+            println!("Run `rufm db migrate` to apply the pending migrations");
+        } else {
+            println!("The database is up to date");
+        }
+
+        Ok(())
+    }
+}
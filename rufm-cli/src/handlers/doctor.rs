@@ -0,0 +1,42 @@
+use colored::*;
+use rufm_core::IntegrityRepository;
+
+use crate::{handlers::Handler, DoctorOpt};
+
+impl Handler for DoctorOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        let problems = client.check_integrity()?;
+        if problems.is_empty() {
+            println!("No problems found");
+
+            return Ok(());
+        }
+
+        let mut left = 0;
+        for problem in &problems {
+            let (status, is_fixed) = if !problem.is_fixable() {
+                ("to fix by hand".red(), false)
+            } else if !self.fix {
+                ("fixable with --fix".yellow(), false)
+            } else {
+                // A failed fix leaves the other problems to fix.
+                match client.transaction(|| client.fix_problem(problem)) {
+                    Ok(true) => ("fixed".green(), true),
+                    Ok(false) => ("not fixed, fix the other problems first".red(), false),
+                    Err(error) => (format!("not fixed: {}", error).red(), false),
+                }
+            };
+            if !is_fixed {
+                left += 1;
+            }
+
+            println!("{} ({})", problem, status);
+        }
+
+        if left > 0 {
+            Err(format!("{} of {} problems left", left, problems.len()).into())
+        } else {
+            Ok(())
+        }
+    }
+}
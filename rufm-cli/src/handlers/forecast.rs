@@ -0,0 +1,52 @@
+use colored::*;
+use rufm_core::{forecast::first_negative_day, AccountsRepository};
+
+use crate::{handlers::Handler, ForecastOpt, Money};
+
+impl Handler for ForecastOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        let account = client.get_account_by_name(&self.account)?;
+        let today = chrono::Local::now().naive_local().date();
+
+        let forecast = client.forecast_account_balance(&account.id, &today, self.days)?;
+        let first_negative_date = first_negative_day(&forecast).map(|day| day.date);
+
+        let mut previous_balance = None;
+        for daily_balance in &forecast {
+            // Only the days the balance changes on, to keep long forecasts
+            // readable.
+            if previous_balance == Some(daily_balance.balance)
+                && first_negative_date != Some(daily_balance.date)
+            {
+                continue;
+            }
+            previous_balance = Some(daily_balance.balance);
+
+            let line = format!("{}  {}", daily_balance.date, Money(daily_balance.balance));
+            if first_negative_date == Some(daily_balance.date) {
+                println!(
+                    "{}  {}",
+                    line.bold(),
+                    "<- first day below zero".red().bold()
+                );
+            } else {
+                println!("{}", line);
+            }
+        }
+
+        match first_negative_date {
+            Some(date) => println!(
+                "\n{}",
+                format!("{} goes below zero on {}", account.name, date)
+                    .red()
+                    .bold()
+            ),
+            None => println!(
+                "\n{} stays above zero over the next {} days",
+                account.name, self.days
+            ),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+use rufm_core::import::ImportReport;
+
+use crate::{handlers::import::ImportHandler, ImportCamt053Opt};
+
+impl ImportHandler for ImportCamt053Opt {
+    fn import(
+        &self,
+        client: &rufm_core::Client,
+    ) -> Result<ImportReport, Box<dyn std::error::Error>> {
+        let file = std::fs::File::open(&self.file)?;
+
+        Ok(rufm_import_camt053::import_camt053(
+            client,
+            file,
+            self.account.as_deref(),
+        )?)
+    }
+}
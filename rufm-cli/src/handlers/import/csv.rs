@@ -0,0 +1,79 @@
+use std::io::Write;
+
+use rufm_core::import::ImportReport;
+use rufm_import_csv::{ImportCsvError, Profile, Row};
+
+use crate::{handlers::import::ImportHandler, ImportCsvOpt, Money};
+
+const PREVIEW_ROWS: usize = 5;
+
+impl ImportHandler for ImportCsvOpt {
+    fn import(
+        &self,
+        client: &rufm_core::Client,
+    ) -> Result<ImportReport, Box<dyn std::error::Error>> {
+        let (rows, errors) = self.read()?;
+
+        let mut report = rufm_import_csv::import_rows(client, &rows, &self.account)?;
+        for error in errors {
+            report.add_error(error.line(), error);
+        }
+
+        Ok(report)
+    }
+
+    fn confirm(&self) -> Result<bool, Box<dyn std::error::Error>> {
+        let (rows, _) = self.read()?;
+        for row in rows.iter().take(PREVIEW_ROWS) {
+            println!(
+                "{:>5}  {}  {:40} {}",
+                row.line,
+                row.date,
+                row.description
+                    .as_deref()
+                    .or(row.counterparty.as_deref())
+                    .unwrap_or_default(),
+                Money(row.amount),
+            );
+        }
+        if rows.len() > PREVIEW_ROWS {
+            println!("  ... and {} more rows", rows.len() - PREVIEW_ROWS);
+        }
+
+        Ok(self.yes
+            || confirm(&format!(
+                "Import {} rows into '{}'?",
+                rows.len(),
+                self.account
+            ))?)
+    }
+}
+
+impl ImportCsvOpt {
+    /// The rows of the file, and the errors of those which cannot be read.
+    fn read(&self) -> Result<(Vec<Row>, Vec<ImportCsvError>), Box<dyn std::error::Error>> {
+        let profile = Profile::load(&self.profile)?;
+        let file = std::fs::File::open(&self.file)?;
+
+        let mut rows = Vec::new();
+        let mut errors = Vec::new();
+        for record in rufm_import_csv::read_records(&profile, file)? {
+            match record {
+                Ok(row) => rows.push(row),
+                Err(error) => errors.push(error),
+            }
+        }
+
+        Ok((rows, errors))
+    }
+}
+
+fn confirm(question: &str) -> std::io::Result<bool> {
+    print!("{} [y/N] ", question);
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
@@ -0,0 +1,93 @@
+use std::io::Write;
+
+use indicatif::{ProgressBar, ProgressStyle};
+use rufm_core::import::ImportReport;
+use rufm_import_firefly_iii::ImportOptions;
+
+use crate::{handlers::import::ImportHandler, ImportFireflyIiiOpt};
+
+/// Number of entries of each kind listed in the report of an export, which
+/// may hold years of transactions.
+const REPORT_SAMPLE_SIZE: usize = 100;
+
+impl ImportHandler for ImportFireflyIiiOpt {
+    fn import(
+        &self,
+        client: &rufm_core::Client,
+    ) -> Result<ImportReport, Box<dyn std::error::Error>> {
+        let report = match (&self.export_file, &self.url, &self.token) {
+            (_, Some(_), Some(_)) => {
+                let api_export = self
+                    .api_export
+                    .get()
+                    .ok_or("the Firefly III API must be read before importing")?;
+                rufm_import_firefly_iii::import_firefly_iii_api_export(client, api_export)?
+            }
+            (Some(export_file), _, _) => {
+                let file = std::fs::File::open(export_file)?;
+                let progress_bar = ProgressBar::new(file.metadata()?.len());
+                progress_bar.set_style(
+                    ProgressStyle::default_bar().template("{bar:40} {bytes}/{total_bytes} ({eta})"),
+                );
+
+                let mut on_progress = |bytes_read| progress_bar.set_position(bytes_read);
+                let options = ImportOptions {
+                    skip_invalid_rows: self.skip_invalid_rows,
+                    on_progress: Some(&mut on_progress),
+                    report_sample_size: Some(REPORT_SAMPLE_SIZE),
+                    ..ImportOptions::default()
+                };
+                let report =
+                    rufm_import_firefly_iii::import_firefly_iii_with_options(client, file, options);
+                progress_bar.finish_and_clear();
+
+                report?
+            }
+            _ => unreachable!("structopt requires an export file or an API URL and token"),
+        };
+
+        Ok(report)
+    }
+
+    fn prepare(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if let (Some(url), Some(token)) = (&self.url, &self.token) {
+            let api = rufm_import_firefly_iii::FireflyIiiApi::new(url, token);
+            // The import attempts, retried while the database is busy, all
+            // read what is fetched once here.
+            let _ = self.api_export.set(api.export()?);
+        }
+
+        Ok(())
+    }
+
+    fn skips_invalid_rows(&self) -> bool {
+        self.skip_invalid_rows
+    }
+
+    fn after_import(
+        &self,
+        report: &ImportReport,
+        dry_run: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match &self.rejects {
+            Some(rejects) if dry_run => {
+                println!("Skipped records would be written to {}", rejects);
+            }
+            Some(rejects) => write_rejects(rejects, report)?,
+            None => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes the line number and error of each skipped record, tab separated.
+fn write_rejects(path: &str, report: &ImportReport) -> Result<(), std::io::Error> {
+    let mut file = std::fs::File::create(path)?;
+    for error in &report.errors {
+        let line = error.line.map(|line| line.to_string()).unwrap_or_default();
+        writeln!(file, "{}\t{}", line, error.message)?;
+    }
+
+    Ok(())
+}
@@ -1,12 +1,278 @@
-use crate::{handlers::Handler, ImportCommand};
+use std::{io::Read, path::Path};
 
-impl Handler for ImportCommand {
-    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
-        let ImportCommand::FireflyIii { export_file } = self;
+use rufm_core::import::{ImportReport, ImporterRegistry};
+
+use crate::{handlers::Handler, ImportCommand, ImportOpt};
+
+#[cfg(feature = "import-camt053")]
+mod camt053;
+#[cfg(feature = "import-csv")]
+mod csv;
+#[cfg(feature = "import-firefly-iii")]
+mod firefly_iii;
+#[cfg(feature = "import-ofx")]
+mod ofx;
+#[cfg(feature = "import-qif")]
+mod qif;
+
+/// Number of bytes importers detect the format of a file from.
+const HEAD_LENGTH: u64 = 4096;
+
+/// An import subcommand, whose report is printed by `rufm import`.
+pub trait ImportHandler {
+    fn import(
+        &self,
+        client: &rufm_core::Client,
+    ) -> Result<ImportReport, Box<dyn std::error::Error>>;
+
+    /// Whether the records reported as errors may be skipped, importing the
+    /// others anyway.
+    fn skips_invalid_rows(&self) -> bool {
+        false
+    }
 
-        let file = std::fs::File::open(&export_file)?;
-        rufm_import_firefly_iii::import_firefly_iii(client, &file)?;
+    /// Reads what is imported from the network, before the database is
+    /// locked, so that no lock is held while waiting for it.
+    fn prepare(&self) -> Result<(), Box<dyn std::error::Error>> {
+        Ok(())
+    }
+
+    /// Asks whether to import, e.g. after a preview. Dry runs import without
+    /// asking.
+    fn confirm(&self) -> Result<bool, Box<dyn std::error::Error>> {
+        Ok(true)
+    }
 
+    /// Runs once the import is over, e.g. to write the records it skipped to
+    /// a file. A dry run must not write anything.
+    fn after_import(
+        &self,
+        _report: &ImportReport,
+        _dry_run: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         Ok(())
     }
 }
+
+impl Handler for ImportOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        self.prepare()?;
+
+        if self.dry_run {
+            let report = client.retry_if_busy(|| client.dry_run(|| self.import(client)))?;
+            println!("{}", report);
+            self.after_import(&report, true)?;
+            println!("Dry run, nothing was imported");
+
+            return Ok(());
+        }
+
+        if !self.confirm()? {
+            println!("Nothing was imported");
+
+            return Ok(());
+        }
+
+        // Another import of the same file would not see the transactions of
+        // this one as duplicates until it is committed.
+        let _lock = client.lock("import")?;
+        if self.skips_invalid_rows() {
+            // The importer commits what it can as it goes. Importing again
+            // after another connection kept the database locked skips the
+            // transactions already committed as duplicates.
+            let report = client.retry_if_busy(|| self.import(client))?;
+            println!("{}", report);
+
+            return self.after_import(&report, false);
+        }
+
+        let report = client.retry_if_busy(|| {
+            client.transaction::<_, Box<dyn std::error::Error>, _>(|| {
+                let report = self.import(client)?;
+                if report.has_errors() {
+                    println!("{}", report);
+
+                    return Err(format!(
+                        "{} records could not be imported, nothing was imported",
+                        report.errors.len()
+                    )
+                    .into());
+                }
+
+                Ok(report)
+            })
+        })?;
+        println!("{}", report);
+
+        self.after_import(&report, false)
+    }
+}
+
+impl ImportHandler for ImportOpt {
+    fn import(
+        &self,
+        client: &rufm_core::Client,
+    ) -> Result<ImportReport, Box<dyn std::error::Error>> {
+        match (&self.command, &self.file) {
+            (Some(command), _) => command.import(client),
+            (None, Some(file)) => import_file(
+                client,
+                &importers(self)?,
+                file,
+                self.format.as_deref(),
+                self.account.as_deref(),
+            ),
+            (None, None) => Err("a file or an import subcommand is required".into()),
+        }
+    }
+
+    fn skips_invalid_rows(&self) -> bool {
+        matches!(&self.command, Some(command) if command.skips_invalid_rows())
+    }
+
+    fn prepare(&self) -> Result<(), Box<dyn std::error::Error>> {
+        match &self.command {
+            Some(command) => command.prepare(),
+            None => Ok(()),
+        }
+    }
+
+    fn confirm(&self) -> Result<bool, Box<dyn std::error::Error>> {
+        match &self.command {
+            Some(command) => command.confirm(),
+            None => Ok(true),
+        }
+    }
+
+    fn after_import(
+        &self,
+        report: &ImportReport,
+        dry_run: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match &self.command {
+            Some(command) => command.after_import(report, dry_run),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ImportHandler for ImportCommand {
+    fn import(
+        &self,
+        client: &rufm_core::Client,
+    ) -> Result<ImportReport, Box<dyn std::error::Error>> {
+        match self {
+            #[cfg(feature = "import-firefly-iii")]
+            ImportCommand::FireflyIii(import_firefly_iii_opt) => {
+                import_firefly_iii_opt.import(client)
+            }
+            #[cfg(feature = "import-ofx")]
+            ImportCommand::Ofx(import_ofx_opt) => import_ofx_opt.import(client),
+            #[cfg(feature = "import-qif")]
+            ImportCommand::Qif(import_qif_opt) => import_qif_opt.import(client),
+            #[cfg(feature = "import-camt053")]
+            ImportCommand::Camt053(import_camt053_opt) => import_camt053_opt.import(client),
+            #[cfg(feature = "import-csv")]
+            ImportCommand::Csv(import_csv_opt) => import_csv_opt.import(client),
+        }
+    }
+
+    fn skips_invalid_rows(&self) -> bool {
+        match self {
+            #[cfg(feature = "import-firefly-iii")]
+            ImportCommand::FireflyIii(import_firefly_iii_opt) => {
+                import_firefly_iii_opt.skips_invalid_rows()
+            }
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    fn prepare(&self) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            #[cfg(feature = "import-firefly-iii")]
+            ImportCommand::FireflyIii(import_firefly_iii_opt) => import_firefly_iii_opt.prepare(),
+            #[allow(unreachable_patterns)]
+            _ => Ok(()),
+        }
+    }
+
+    fn confirm(&self) -> Result<bool, Box<dyn std::error::Error>> {
+        match self {
+            #[cfg(feature = "import-csv")]
+            ImportCommand::Csv(import_csv_opt) => import_csv_opt.confirm(),
+            #[allow(unreachable_patterns)]
+            _ => Ok(true),
+        }
+    }
+
+    fn after_import(
+        &self,
+        report: &ImportReport,
+        dry_run: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            #[cfg(feature = "import-firefly-iii")]
+            ImportCommand::FireflyIii(import_firefly_iii_opt) => {
+                import_firefly_iii_opt.after_import(report, dry_run)
+            }
+            #[allow(unreachable_patterns)]
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The importers `rufm import <file>` chooses among. CSV files are only
+/// imported given the profile describing their layout.
+#[allow(unused_mut, unused_variables)]
+fn importers(opt: &ImportOpt) -> Result<ImporterRegistry, Box<dyn std::error::Error>> {
+    let mut importers = ImporterRegistry::default();
+    #[cfg(feature = "import-firefly-iii")]
+    rufm_import_firefly_iii::register(&mut importers);
+    #[cfg(feature = "import-ofx")]
+    rufm_import_ofx::register(&mut importers);
+    #[cfg(feature = "import-qif")]
+    rufm_import_qif::register(&mut importers);
+    #[cfg(feature = "import-camt053")]
+    rufm_import_camt053::register(&mut importers);
+    #[cfg(feature = "import-csv")]
+    if let Some(profile) = &opt.profile {
+        rufm_import_csv::register(&mut importers, rufm_import_csv::Profile::load(profile)?);
+    }
+
+    Ok(importers)
+}
+
+/// Imports a file with the importer of `format`, or the one detecting it.
+fn import_file(
+    client: &rufm_core::Client,
+    importers: &ImporterRegistry,
+    path: &str,
+    format: Option<&str>,
+    account: Option<&str>,
+) -> Result<ImportReport, Box<dyn std::error::Error>> {
+    let names = importers
+        .importers()
+        .map(|importer| importer.name())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut file = std::fs::File::open(path)?.take(HEAD_LENGTH);
+    let mut head = Vec::new();
+    file.read_to_end(&mut head)?;
+
+    let importer = match format {
+        Some(format) => importers
+            .get(format)
+            .ok_or_else(|| format!("unknown format '{}', expected one of: {}", format, names))?,
+        None => importers.detect(Path::new(path), &head).ok_or_else(|| {
+            format!(
+                "could not detect the format of '{}', use --format with one of: {}",
+                path, names
+            )
+        })?,
+    };
+
+    let mut rdr = head.as_slice().chain(file.into_inner());
+    importer.import(client, &mut rdr, account)
+}
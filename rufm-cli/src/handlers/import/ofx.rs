@@ -0,0 +1,14 @@
+use rufm_core::import::ImportReport;
+
+use crate::{handlers::import::ImportHandler, ImportOfxOpt};
+
+impl ImportHandler for ImportOfxOpt {
+    fn import(
+        &self,
+        client: &rufm_core::Client,
+    ) -> Result<ImportReport, Box<dyn std::error::Error>> {
+        let file = std::fs::File::open(&self.file)?;
+
+        Ok(rufm_import_ofx::import_ofx(client, file, &self.account)?)
+    }
+}
@@ -0,0 +1,19 @@
+use rufm_core::import::ImportReport;
+
+use crate::{handlers::import::ImportHandler, ImportQifOpt};
+
+impl ImportHandler for ImportQifOpt {
+    fn import(
+        &self,
+        client: &rufm_core::Client,
+    ) -> Result<ImportReport, Box<dyn std::error::Error>> {
+        let file = std::fs::File::open(&self.file)?;
+
+        Ok(rufm_import_qif::import_qif(
+            client,
+            file,
+            &self.account,
+            self.date_format.as_deref(),
+        )?)
+    }
+}
@@ -1,8 +1,16 @@
 pub mod accounts;
-#[cfg(feature = "import-firefly-iii")]
+pub mod db;
+pub mod doctor;
+pub mod forecast;
+#[cfg(feature = "import")]
 pub mod import;
+pub mod payees;
+pub mod recurring;
+pub mod report;
+pub mod rules;
 pub mod transactions;
 
+#[allow(unused_imports)]
 pub use accounts::*;
 
 use crate::Command;
@@ -16,7 +24,14 @@ impl Handler for Command {
         match self {
             Command::Accounts(accounts_command) => accounts_command.handle(client),
             Command::Transactions(transactions_command) => transactions_command.handle(client),
-            #[cfg(feature = "import-firefly-iii")]
+            Command::Payees(payees_command) => payees_command.handle(client),
+            Command::Rules(rules_command) => rules_command.handle(client),
+            Command::Recurring(recurring_command) => recurring_command.handle(client),
+            Command::Forecast(forecast_opt) => forecast_opt.handle(client),
+            Command::Report(report_command) => report_command.handle(client),
+            Command::Doctor(doctor_opt) => doctor_opt.handle(client),
+            Command::Db(db_command) => db_command.handle(client),
+            #[cfg(feature = "import")]
             Command::Import(import_command) => import_command.handle(client),
         }
     }
@@ -0,0 +1,13 @@
+use rufm_core::PayeesRepository;
+
+use crate::{handlers::Handler, PayeesAliasOpt};
+
+impl Handler for PayeesAliasOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        let payee = client.get_payee_by_name(&self.name)?;
+
+        client.add_payee_alias(&payee.id, &self.alias)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use rufm_core::{models::payees::NewPayee, AccountsRepository, PayeesRepository};
+
+use crate::{
+    handlers::{payees::get_or_create_category, Handler},
+    PayeesCreateOpt,
+};
+
+impl Handler for PayeesCreateOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        let account = self
+            .account
+            .as_deref()
+            .map(|name| client.get_account_by_name(name))
+            .transpose()?;
+
+        client.transaction(|| {
+            let category = self
+                .category
+                .as_deref()
+                .map(|name| get_or_create_category(client, name))
+                .transpose()?;
+
+            client.create_payee(&NewPayee {
+                name: &self.name,
+                default_category_id: category.map(|category| category.id),
+                default_account_id: account.map(|account| account.id),
+            })
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,27 @@
+use rufm_core::{PayeesRepository, TransactionsRepository};
+
+use crate::{handlers::Handler, Money, PayeesListOpt};
+
+impl Handler for PayeesListOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        for payee in client.list_payees()? {
+            let transactions = client.get_transactions_for_payee(&payee.id)?;
+            let total = transactions
+                .iter()
+                .map(|transaction| transaction.amount)
+                .sum::<i64>();
+
+            println!(
+                "{:50} {:4} transactions {}",
+                payee.name,
+                transactions.len(),
+                Money(total)
+            );
+            for alias in client.get_aliases_for_payee(&payee.id)? {
+                println!("  aka {}", alias);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use rufm_core::{
+    models::categories::{Category, NewCategory},
+    CategoriesRepository,
+    OptionalExtension,
+};
+
+use crate::{handlers::Handler, PayeesCommand, PayeesListOpt};
+
+mod alias;
+mod create;
+mod list;
+mod update;
+
+impl Handler for PayeesCommand {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            PayeesCommand::Create(payees_create_opt) => payees_create_opt.handle(client),
+            PayeesCommand::List => PayeesListOpt.handle(client),
+            PayeesCommand::Alias(payees_alias_opt) => payees_alias_opt.handle(client),
+            PayeesCommand::Update(payees_update_opt) => payees_update_opt.handle(client),
+        }
+    }
+}
+
+fn get_or_create_category(
+    client: &rufm_core::Client,
+    name: &str,
+) -> rufm_core::QueryResult<Category> {
+    match client.get_category_by_name(name).optional()? {
+        Some(category) => Ok(category),
+        None => client.create_category(&NewCategory { name }),
+    }
+}
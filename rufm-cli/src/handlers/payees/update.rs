@@ -0,0 +1,25 @@
+use rufm_core::{AccountsRepository, PayeesRepository};
+
+use crate::{
+    handlers::{payees::get_or_create_category, Handler},
+    PayeesUpdateOpt,
+};
+
+impl Handler for PayeesUpdateOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        let mut payee = client.get_payee_by_name(&self.name)?;
+        if let Some(name) = &self.account {
+            payee.default_account_id = Some(client.get_account_by_name(name)?.id);
+        }
+
+        client.transaction(|| {
+            if let Some(name) = &self.category {
+                payee.default_category_id = Some(get_or_create_category(client, name)?.id);
+            }
+
+            client.update_payee_defaults(&payee)
+        })?;
+
+        Ok(())
+    }
+}
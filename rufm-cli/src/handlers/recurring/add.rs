@@ -0,0 +1,28 @@
+use rufm_core::{
+    models::recurring_transactions::NewRecurringTransaction,
+    AccountsRepository,
+    RecurringTransactionsRepository,
+};
+
+use crate::{handlers::Handler, RecurringAddOpt};
+
+impl Handler for RecurringAddOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        let source_account = client.get_account_by_name(&self.source_account)?;
+        let destination_account = client.get_account_by_name(&self.destination_account)?;
+
+        client.create_recurring_transaction(&NewRecurringTransaction {
+            name: &self.name,
+            source_account_id: source_account.id,
+            destination_account_id: destination_account.id,
+            amount: self.amount.0,
+            frequency: self.every,
+            start_date: self
+                .start
+                .unwrap_or_else(|| chrono::Local::now().naive_local().date()),
+            end_date: self.end,
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use rufm_core::{AccountsRepository, RecurringTransactionsRepository};
+
+use crate::{handlers::Handler, Money, RecurringListOpt};
+
+impl Handler for RecurringListOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        for recurring_transaction in client.list_recurring_transactions()? {
+            let source_account =
+                client.get_account_by_id(&recurring_transaction.source_account_id)?;
+            let destination_account =
+                client.get_account_by_id(&recurring_transaction.destination_account_id)?;
+
+            println!(
+                "{:30} {} {:?} from {}{}  {} -> {}",
+                recurring_transaction.name,
+                Money(recurring_transaction.amount),
+                recurring_transaction.frequency,
+                recurring_transaction.start_date,
+                recurring_transaction
+                    .end_date
+                    .map(|end_date| format!(" to {}", end_date))
+                    .unwrap_or_default(),
+                source_account.name,
+                destination_account.name,
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,13 @@
+use crate::{handlers::Handler, RecurringCommand, RecurringListOpt};
+
+mod add;
+mod list;
+
+impl Handler for RecurringCommand {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            RecurringCommand::Add(recurring_add_opt) => recurring_add_opt.handle(client),
+            RecurringCommand::List => RecurringListOpt.handle(client),
+        }
+    }
+}
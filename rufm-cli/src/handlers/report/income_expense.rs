@@ -0,0 +1,48 @@
+use rufm_core::{reports::IncomeExpense, ReportsRepository};
+
+use crate::{handlers::Handler, Money, ReportIncomeExpenseOpt};
+
+impl Handler for ReportIncomeExpenseOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        let report = client.get_income_expense_report(&self.from, &self.to)?;
+
+        println!(
+            "{:7}  {:>12}  {:>12}  {:>12}  {:>8}",
+            "Month", "Income", "Expense", "Net", "Savings"
+        );
+        for income_expense in &report {
+            print_row(&income_expense.month.to_string(), income_expense);
+        }
+
+        let total = report.iter().fold(
+            IncomeExpense {
+                month: self.from,
+                income: 0,
+                expense: 0,
+            },
+            |total, income_expense| IncomeExpense {
+                income: total.income + income_expense.income,
+                expense: total.expense + income_expense.expense,
+                ..total
+            },
+        );
+        println!(" -- ");
+        print_row("Total", &total);
+
+        Ok(())
+    }
+}
+
+fn print_row(label: &str, income_expense: &IncomeExpense) {
+    println!(
+        "{:7}  {}  {}  {}  {:>8}",
+        label,
+        Money(income_expense.income),
+        Money(-income_expense.expense),
+        Money(income_expense.net()),
+        income_expense
+            .savings_rate()
+            .map(|savings_rate| format!("{:.1} %", savings_rate * 100.0))
+            .unwrap_or_else(|| "-".to_string()),
+    );
+}
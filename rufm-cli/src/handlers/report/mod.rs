@@ -0,0 +1,19 @@
+use crate::{handlers::Handler, ReportCommand};
+
+mod income_expense;
+mod net_worth;
+mod spending;
+mod summary;
+
+impl Handler for ReportCommand {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            ReportCommand::IncomeExpense(report_income_expense_opt) => {
+                report_income_expense_opt.handle(client)
+            }
+            ReportCommand::NetWorth(report_net_worth_opt) => report_net_worth_opt.handle(client),
+            ReportCommand::Spending(report_spending_opt) => report_spending_opt.handle(client),
+            ReportCommand::Summary(report_summary_opt) => report_summary_opt.handle(client),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use rufm_core::ReportsRepository;
+
+use crate::{charts, handlers::Handler, Money, ReportNetWorthOpt};
+
+impl Handler for ReportNetWorthOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        let to = self
+            .to
+            .unwrap_or_else(|| chrono::Local::now().naive_local().date());
+        let report = client.get_net_worth_report(&self.from, &to, self.every)?;
+
+        if self.chart {
+            charts::print_balances_sparkline(
+                &report
+                    .iter()
+                    .map(|net_worth| (net_worth.date, net_worth.net_worth))
+                    .collect::<Vec<_>>(),
+            );
+
+            return Ok(());
+        }
+
+        println!("{:10}  {:>12}  {:>12}", "Date", "Net worth", "Change");
+        let mut previous_net_worth = None;
+        for net_worth in &report {
+            println!(
+                "{}  {}  {}",
+                net_worth.date,
+                Money(net_worth.net_worth),
+                previous_net_worth
+                    .map(
+                        |previous_net_worth| Money(net_worth.net_worth - previous_net_worth)
+                            .to_string()
+                    )
+                    .unwrap_or_default(),
+            );
+            previous_net_worth = Some(net_worth.net_worth);
+        }
+
+        Ok(())
+    }
+}
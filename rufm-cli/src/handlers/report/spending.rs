@@ -0,0 +1,83 @@
+use colored::*;
+use rufm_core::{
+    reports::{Month, SpendingReport},
+    ReportsRepository,
+};
+
+use crate::{charts, handlers::Handler, Money, ReportSpendingOpt};
+
+impl Handler for ReportSpendingOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        let month = self
+            .period
+            .unwrap_or_else(|| Month::of(&chrono::Local::now().naive_local().date()));
+        let report = client.get_spending_report(&month, self.by)?;
+
+        println!(
+            "{:40} {:>12}  {:>6}  {:>12}",
+            "",
+            "Total",
+            "Share",
+            format!("vs {}", month.previous())
+        );
+        for spending in &report.spendings {
+            println!(
+                "{:40} {}  {:5.1}%  {}",
+                spending.name.as_deref().unwrap_or("(none)"),
+                Money(-spending.total),
+                spending.share * 100.0,
+                Money(-spending.change()),
+            );
+        }
+        println!(" -- ");
+        println!(
+            "{:40} {}  {:5.1}%  {}",
+            "Total",
+            Money(-report.total),
+            if report.total == 0 { 0.0 } else { 100.0 },
+            Money(report.previous_total - report.total),
+        );
+
+        if self.chart {
+            println!();
+            print_bar_chart(&report);
+
+            println!();
+            let daily_spending = client
+                .get_daily_spending_report(&month)?
+                .into_iter()
+                .map(|daily_spending| (daily_spending.date, daily_spending.total))
+                .collect::<Vec<_>>();
+            for line in charts::calendar_heat_map(&daily_spending) {
+                println!("{}", line);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn print_bar_chart(report: &SpendingReport) {
+    let max = report
+        .spendings
+        .iter()
+        .map(|spending| spending.total)
+        .max()
+        .unwrap_or(0);
+    let width = charts::terminal_width().saturating_sub(20 + 1 + 8).max(10);
+
+    for spending in &report.spendings {
+        let name = spending.name.as_deref().unwrap_or("(none)");
+        println!(
+            "{:20} {} {:5.1}%",
+            name.chars().take(20).collect::<String>(),
+            format!(
+                "{:width$}",
+                charts::bar(spending.total, max, width),
+                width = width
+            )
+            .red(),
+            spending.share * 100.0,
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use rufm_core::{
+    reports::{Month, Period},
+    ReportsRepository,
+};
+
+use crate::{
+    handlers::Handler,
+    html::{render_summary, Summary},
+    ReportSummaryOpt,
+};
+
+impl Handler for ReportSummaryOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        let month = self
+            .period
+            .unwrap_or_else(|| Month::of(&chrono::Local::now().naive_local().date()));
+        let first_month = (0..11).fold(month, |month, _| month.previous());
+
+        let income_expense = client.get_income_expense_report(&first_month, &month)?;
+        let net_worth = client.get_net_worth_report(
+            &first_month.first_day(),
+            &month.last_day(),
+            Period::Week,
+        )?;
+        let spending = client.get_spending_report(&month, self.by)?;
+
+        std::fs::write(
+            &self.output,
+            render_summary(&Summary {
+                month,
+                income_expense: &income_expense,
+                net_worth: &net_worth,
+                spending: &spending,
+            }),
+        )?;
+        println!("Summary of {} written to {}", month, self.output);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use rufm_core::{
+    models::{categories::NewCategory, rules::NewRule, tags::NewTag},
+    AccountsRepository,
+    CategoriesRepository,
+    OptionalExtension,
+    RulesRepository,
+    TagsRepository,
+};
+
+use crate::{handlers::Handler, RulesAddOpt};
+
+impl Handler for RulesAddOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        if self.rename.is_none()
+            && self.destination_account.is_none()
+            && self.category.is_none()
+            && self.tags.is_empty()
+        {
+            return Err("a rule needs --rename, --destination-account, --category or --tag".into());
+        }
+
+        client.transaction(|| {
+            let source_account = self
+                .source_account
+                .as_deref()
+                .map(|name| client.get_account_by_name(name))
+                .transpose()?;
+            let destination_account = self
+                .destination_account
+                .as_deref()
+                .map(|name| client.get_account_by_name(name))
+                .transpose()?;
+            let category = match self.category.as_deref() {
+                Some(name) => Some(match client.get_category_by_name(name).optional()? {
+                    Some(category) => category,
+                    None => client.create_category(&NewCategory { name })?,
+                }),
+                None => None,
+            };
+
+            let rule = client.create_rule(&NewRule {
+                name: &self.name,
+                name_pattern: self.pattern.as_deref(),
+                min_amount: self.min_amount.as_ref().map(|amount| amount.0),
+                max_amount: self.max_amount.as_ref().map(|amount| amount.0),
+                source_account_id: source_account.map(|account| account.id),
+                set_name: self.rename.as_deref(),
+                set_destination_account_id: destination_account.map(|account| account.id),
+                set_category_id: category.map(|category| category.id),
+            })?;
+
+            for name in &self.tags {
+                let tag = match client.get_tag_by_name(name).optional()? {
+                    Some(tag) => tag,
+                    None => client.create_tag(&NewTag { name })?,
+                };
+                client.add_tag_to_rule(&rule.id, &tag.id)?;
+            }
+
+            Ok::<_, Box<dyn std::error::Error>>(())
+        })
+    }
+}
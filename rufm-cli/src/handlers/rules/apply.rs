@@ -0,0 +1,32 @@
+use rufm_core::{RulesRepository, TransactionsRepository};
+
+use crate::{handlers::Handler, RulesApplyOpt};
+
+impl Handler for RulesApplyOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.retroactive {
+            return Err(
+                "rules are applied to new transactions as they are created, use \
+                        --retroactive to apply them to the existing ones"
+                    .into(),
+            );
+        }
+
+        let changed = client.transaction::<_, Box<dyn std::error::Error>, _>(|| {
+            let mut changed = 0;
+            for transaction in client.list_transactions()? {
+                if !self.include_categorized && transaction.category_id.is_some() {
+                    continue;
+                }
+                if !client.apply_rules(&transaction.id)?.is_empty() {
+                    changed += 1;
+                }
+            }
+
+            Ok(changed)
+        })?;
+        println!("Rules applied to {} transactions", changed);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use rufm_core::{models::rules::Rule, AccountsRepository, CategoriesRepository, RulesRepository};
+
+use crate::{handlers::Handler, Money, RulesListOpt};
+
+impl Handler for RulesListOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        for rule in client.list_rules()? {
+            println!("{}", rule.name);
+            println!("  when {}", describe_conditions(client, &rule)?.join(", "));
+            println!("  then {}", describe_actions(client, &rule)?.join(", "));
+            println!();
+        }
+
+        Ok(())
+    }
+}
+
+fn describe_conditions(
+    client: &rufm_core::Client,
+    rule: &Rule,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut conditions = Vec::new();
+    if let Some(pattern) = &rule.name_pattern {
+        conditions.push(format!("name matches /{}/", pattern));
+    }
+    if let Some(min_amount) = rule.min_amount {
+        conditions.push(format!("amount >= {}", Money(min_amount)));
+    }
+    if let Some(max_amount) = rule.max_amount {
+        conditions.push(format!("amount <= {}", Money(max_amount)));
+    }
+    if let Some(account_id) = &rule.source_account_id {
+        let account = client.get_account_by_id(account_id)?;
+        conditions.push(format!("from '{}'", account.name));
+    }
+    if conditions.is_empty() {
+        conditions.push("any transaction".to_string());
+    }
+
+    Ok(conditions)
+}
+
+fn describe_actions(
+    client: &rufm_core::Client,
+    rule: &Rule,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut actions = Vec::new();
+    if let Some(name) = &rule.set_name {
+        actions.push(format!("rename to '{}'", name));
+    }
+    if let Some(account_id) = &rule.set_destination_account_id {
+        let account = client.get_account_by_id(account_id)?;
+        actions.push(format!("send to '{}'", account.name));
+    }
+    if let Some(category_id) = rule.set_category_id {
+        let category = client
+            .list_categories()?
+            .into_iter()
+            .find(|category| category.id == category_id)
+            .ok_or("unknown category")?;
+        actions.push(format!("categorize as '{}'", category.name));
+    }
+    for tag in client.get_tags_for_rule(&rule.id)? {
+        actions.push(format!("tag with '{}'", tag.name));
+    }
+
+    Ok(actions)
+}
@@ -0,0 +1,17 @@
+use crate::{handlers::Handler, RulesCommand, RulesListOpt};
+
+mod add;
+mod apply;
+mod list;
+mod test;
+
+impl Handler for RulesCommand {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            RulesCommand::Add(rules_add_opt) => rules_add_opt.handle(client),
+            RulesCommand::List => RulesListOpt.handle(client),
+            RulesCommand::Test(rules_test_opt) => rules_test_opt.handle(client),
+            RulesCommand::Apply(rules_apply_opt) => rules_apply_opt.handle(client),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use rufm_core::{RulesRepository, TransactionsRepository};
+
+use crate::{handlers::Handler, Money, RulesTestOpt};
+
+impl Handler for RulesTestOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(name) = &self.name {
+            client.get_rule_by_name(name)?;
+        }
+
+        for transaction in client.list_transactions()? {
+            let rules = client
+                .get_matching_rules(&transaction)?
+                .into_iter()
+                .map(|rule| rule.name)
+                .filter(|rule_name| match &self.name {
+                    Some(name) => name == rule_name,
+                    None => true,
+                })
+                .collect::<Vec<_>>();
+
+            if !rules.is_empty() {
+                println!(
+                    "{} {:40} {}  ({})",
+                    transaction.date,
+                    transaction.name,
+                    Money(transaction.amount),
+                    rules.join(", ")
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
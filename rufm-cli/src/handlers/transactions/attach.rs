@@ -0,0 +1,31 @@
+use rufm_core::{
+    models::{
+        attachments::{content_type_from_file_name, NewAttachment},
+        transactions::TransactionId,
+    },
+    AttachmentsRepository,
+    TransactionsRepository,
+};
+
+use crate::{handlers::Handler, TransactionsAttachOpt};
+
+impl Handler for TransactionsAttachOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        let transaction = client.get_transaction_by_id(&TransactionId(self.id))?;
+        let path = std::path::Path::new(&self.file);
+        let file_name = path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .ok_or("invalid file name")?;
+        let content = std::fs::read(path)?;
+
+        client.create_attachment(&NewAttachment {
+            transaction_id: transaction.id,
+            file_name,
+            content_type: content_type_from_file_name(file_name),
+            content: &content,
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use std::{io::Write, path::Path};
+
+use rufm_core::{
+    models::{attachments::Attachment, transactions::TransactionId},
+    AttachmentsRepository,
+    TransactionsRepository,
+};
+
+use crate::{handlers::Handler, TransactionsAttachmentsOpt};
+
+impl Handler for TransactionsAttachmentsOpt {
+    fn handle(&self, client: &rufm_core::Client) -> Result<(), Box<dyn std::error::Error>> {
+        let transaction = client.get_transaction_by_id(&TransactionId(self.id))?;
+        let attachments = client.get_attachments_for_transaction(&transaction.id)?;
+
+        for attachment in &attachments {
+            println!(
+                "{:40} {:25} {:>10} bytes",
+                attachment.file_name,
+                attachment.content_type,
+                attachment.content.len()
+            );
+        }
+
+        if let Some(directory) = &self.extract {
+            let paths = attachments
+                .iter()
+                .map(|attachment| {
+                    Path::new(directory).join(extracted_file_name(attachment, &attachments))
+                })
+                .collect::<Vec<_>>();
+            // Never overwrite a file, such as one extracted before, and check
+            // them all before extracting any.
+            if let Some(path) = paths.iter().find(|path| path.exists()) {
+                return Err(format!("'{}' already exists", path.display()).into());
+            }
+
+            for (attachment, path) in attachments.iter().zip(&paths) {
+                let mut file = std::fs::OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(path)?;
+                file.write_all(&attachment.content)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The name of the file `attachment` is extracted to: its file name without
+/// any directory, so that it stays in the extraction directory, prefixed by
+/// its ID when other attachments of the transaction share it.
+fn extracted_file_name(attachment: &Attachment, attachments: &[Attachment]) -> String {
+    let file_name = |attachment: &Attachment| {
+        Path::new(&attachment.file_name)
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+    };
+
+    match file_name(attachment) {
+        Some(name)
+            if attachments
+                .iter()
+                .filter(|other| file_name(other).as_ref() == Some(&name))
+                .count()
+                == 1 =>
+        {
+            name
+        }
+        Some(name) => format!("{}-{}", attachment.id.0, name),
+        None => format!("attachment-{}", attachment.id.0),
+    }
+}
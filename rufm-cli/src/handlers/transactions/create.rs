@@ -1,3 +1,4 @@
+use chrono::Timelike;
 use rufm_core::{models::transactions::NewTransaction, AccountsRepository, TransactionsRepository};
 
 use crate::{handlers::Handler, TransactionsCreateOpt};
@@ -7,12 +8,21 @@ impl Handler for TransactionsCreateOpt {
         let source_account = client.get_account_by_name(&self.source_account)?;
         let destination_account = client.get_account_by_name(&self.destination_account)?;
 
+        let now = chrono::Local::now();
+
         client.create_transaction(&NewTransaction {
             name: &self.name,
             amount: self.amount.0,
             source_account_id: source_account.id,
             destination_account_id: destination_account.id,
-            date: chrono::Local::now().naive_local().date(),
+            date: now.naive_local().date(),
+            external_id: None,
+            category_id: None,
+            budget_id: None,
+            notes: self.notes.as_deref(),
+            payee_id: None,
+            time: now.time().with_nanosecond(0),
+            utc_offset: Some(now.offset().local_minus_utc()),
         })?;
 
         Ok(())
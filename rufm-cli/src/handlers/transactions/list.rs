@@ -32,7 +32,12 @@ impl Handler for TransactionsListOpt {
                 >>()?;
 
         for transaction in transactions {
-            println!("{}  {}", transaction.name, Money(transaction.amount));
+            println!(
+                "#{} {}  {}",
+                transaction.id.0,
+                transaction.name,
+                Money(transaction.amount)
+            );
             println!(
                 "{} --> {}",
                 accounts_by_id
@@ -44,6 +49,9 @@ impl Handler for TransactionsListOpt {
                     .expect("account by id")
                     .name,
             );
+            if let Some(notes) = &transaction.notes {
+                println!("{}", notes);
+            }
             println!();
         }
 
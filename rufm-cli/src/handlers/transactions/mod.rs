@@ -1,5 +1,7 @@
 use crate::{handlers::Handler, TransactionsCommand, TransactionsListOpt};
 
+mod attach;
+mod attachments;
 mod create;
 mod list;
 
@@ -10,6 +12,12 @@ impl Handler for TransactionsCommand {
                 transactions_create_opt.handle(client)
             }
             TransactionsCommand::List => TransactionsListOpt.handle(client),
+            TransactionsCommand::Attach(transactions_attach_opt) => {
+                transactions_attach_opt.handle(client)
+            }
+            TransactionsCommand::Attachments(transactions_attachments_opt) => {
+                transactions_attachments_opt.handle(client)
+            }
         }
     }
 }
@@ -0,0 +1,295 @@
+//! Self-contained HTML reports, with inline styles and SVG charts, so that
+//! they can be opened offline or sent by email.
+
+use std::fmt::Write;
+
+use rufm_core::reports::{IncomeExpense, Month, NetWorth, SpendingReport};
+
+const CHART_WIDTH: f64 = 640.0;
+const CHART_HEIGHT: f64 = 220.0;
+const MARGIN: f64 = 40.0;
+
+const STYLE: &str =
+    "body { font-family: sans-serif; max-width: 720px; margin: 2em auto; color: #222; }
+table { border-collapse: collapse; width: 100%; margin: 1em 0; }
+th, td { padding: 0.2em 0.5em; border-bottom: 1px solid #ddd; }
+td.amount, th.amount { text-align: right; font-variant-numeric: tabular-nums; }
+.income { fill: #2e7d32; }
+.expense { fill: #c62828; }
+.negative { color: #c62828; }
+svg text { font-size: 10px; fill: #555; }";
+
+/// The reports of a month.
+pub struct Summary<'a> {
+    pub month: Month,
+    pub income_expense: &'a [IncomeExpense],
+    pub net_worth: &'a [NetWorth],
+    pub spending: &'a SpendingReport,
+}
+
+pub fn render_summary(summary: &Summary) -> String {
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Financial summary \
+         {month}</title>\n<style>\n{style}\n</style>\n</head>\n<body>\n<h1>Financial summary \
+         {month}</h1>\n",
+        month = summary.month,
+        style = STYLE,
+    );
+
+    html.push_str("<h2>Income and expenses</h2>\n");
+    html.push_str(&income_expense_chart(summary.income_expense));
+    html.push_str(&income_expense_table(summary.income_expense));
+
+    html.push_str("<h2>Net worth</h2>\n");
+    html.push_str(&net_worth_chart(summary.net_worth));
+
+    let _ = writeln!(html, "<h2>Spending in {}</h2>", summary.month);
+    html.push_str(&spending_chart(summary.spending));
+    html.push_str(&spending_table(summary.spending));
+
+    html.push_str("</body>\n</html>\n");
+
+    html
+}
+
+fn income_expense_chart(report: &[IncomeExpense]) -> String {
+    let max = report
+        .iter()
+        .map(|income_expense| income_expense.income.max(income_expense.expense))
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let group_width = (CHART_WIDTH - 2.0 * MARGIN) / report.len().max(1) as f64;
+    let bar_width = group_width * 0.4;
+    let bottom = CHART_HEIGHT - MARGIN;
+    let height = |amount: i64| amount as f64 / max as f64 * (bottom - MARGIN / 2.0);
+
+    let mut svg = svg_start(CHART_HEIGHT);
+    for (i, income_expense) in report.iter().enumerate() {
+        let x = MARGIN + i as f64 * group_width;
+        for (offset, class, amount) in [
+            (0.0, "income", income_expense.income),
+            (bar_width, "expense", income_expense.expense),
+        ]
+        .iter()
+        {
+            let _ = writeln!(
+                svg,
+                "<rect class=\"{}\" x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" \
+                 height=\"{:.1}\"><title>{}</title></rect>",
+                class,
+                x + offset,
+                bottom - height(*amount),
+                bar_width,
+                height(*amount),
+                format_amount(*amount),
+            );
+        }
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            x + bar_width,
+            bottom + 14.0,
+            income_expense.month,
+        );
+    }
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+fn income_expense_table(report: &[IncomeExpense]) -> String {
+    let mut table = String::from(
+        "<table>\n<tr><th>Month</th><th class=\"amount\">Income</th><th \
+         class=\"amount\">Expense</th><th class=\"amount\">Net</th><th \
+         class=\"amount\">Savings</th></tr>\n",
+    );
+    for income_expense in report {
+        let _ = writeln!(
+            table,
+            "<tr><td>{}</td>{}{}{}<td class=\"amount\">{}</td></tr>",
+            income_expense.month,
+            amount_cell(income_expense.income),
+            amount_cell(-income_expense.expense),
+            amount_cell(income_expense.net()),
+            income_expense
+                .savings_rate()
+                .map(|savings_rate| format!("{:.1} %", savings_rate * 100.0))
+                .unwrap_or_else(|| "-".to_string()),
+        );
+    }
+    table.push_str("</table>\n");
+
+    table
+}
+
+fn net_worth_chart(report: &[NetWorth]) -> String {
+    let min = report
+        .iter()
+        .map(|net_worth| net_worth.net_worth)
+        .min()
+        .unwrap_or(0)
+        .min(0);
+    let max = report
+        .iter()
+        .map(|net_worth| net_worth.net_worth)
+        .max()
+        .unwrap_or(0)
+        .max(min + 1);
+    let step = (CHART_WIDTH - 2.0 * MARGIN) / (report.len().max(2) - 1) as f64;
+    let bottom = CHART_HEIGHT - MARGIN;
+    let y = |amount: i64| bottom - (amount - min) as f64 / (max - min) as f64 * (bottom - MARGIN);
+
+    let mut svg = svg_start(CHART_HEIGHT);
+    let _ = writeln!(
+        svg,
+        "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#999\" />",
+        MARGIN,
+        y(0),
+        CHART_WIDTH - MARGIN,
+        y(0),
+    );
+    let points = report
+        .iter()
+        .enumerate()
+        .map(|(i, net_worth)| {
+            format!(
+                "{:.1},{:.1}",
+                MARGIN + i as f64 * step,
+                y(net_worth.net_worth)
+            )
+        })
+        .collect::<Vec<_>>();
+    let _ = writeln!(
+        svg,
+        "<polyline points=\"{}\" fill=\"none\" stroke=\"#1565c0\" stroke-width=\"2\" />",
+        points.join(" "),
+    );
+    if let (Some(first), Some(last)) = (report.first(), report.last()) {
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\">{}</text>\n<text x=\"{:.1}\" y=\"{:.1}\" \
+             text-anchor=\"end\">{}</text>",
+            MARGIN,
+            bottom + 14.0,
+            first.date,
+            CHART_WIDTH - MARGIN,
+            bottom + 14.0,
+            last.date,
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+            CHART_WIDTH - MARGIN,
+            y(last.net_worth) - 6.0,
+            format_amount(last.net_worth),
+        );
+    }
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+fn spending_chart(report: &SpendingReport) -> String {
+    let label_width = 160.0;
+    let row_height = 20.0;
+    let max = report
+        .spendings
+        .iter()
+        .map(|spending| spending.total)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let height = row_height * report.spendings.len() as f64 + 10.0;
+
+    let mut svg = svg_start(height);
+    for (i, spending) in report.spendings.iter().enumerate() {
+        let y = i as f64 * row_height + 5.0;
+        let width = spending.total as f64 / max as f64 * (CHART_WIDTH - label_width - 80.0);
+        let _ = writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>\n<rect class=\"expense\" \
+             x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" />\n<text x=\"{:.1}\" \
+             y=\"{:.1}\">{:.1} %</text>",
+            label_width - 8.0,
+            y + 13.0,
+            escape(spending_name(spending.name.as_deref())),
+            label_width,
+            y,
+            width,
+            row_height - 4.0,
+            label_width + width + 6.0,
+            y + 13.0,
+            spending.share * 100.0,
+        );
+    }
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+fn spending_table(report: &SpendingReport) -> String {
+    let mut table = format!(
+        "<table>\n<tr><th></th><th class=\"amount\">Total</th><th \
+         class=\"amount\">Share</th><th class=\"amount\">vs {}</th></tr>\n",
+        report.month.previous(),
+    );
+    for spending in &report.spendings {
+        let _ = writeln!(
+            table,
+            "<tr><td>{}</td>{}<td class=\"amount\">{:.1} %</td>{}</tr>",
+            escape(spending_name(spending.name.as_deref())),
+            amount_cell(-spending.total),
+            spending.share * 100.0,
+            amount_cell(-spending.change()),
+        );
+    }
+    let _ = writeln!(
+        table,
+        "<tr><th>Total</th>{}<td></td>{}</tr>\n</table>",
+        amount_cell(-report.total),
+        amount_cell(report.previous_total - report.total),
+    );
+
+    table
+}
+
+fn svg_start(height: f64) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 \
+         {w} {h}\">\n",
+        w = CHART_WIDTH,
+        h = height,
+    )
+}
+
+fn spending_name(name: Option<&str>) -> &str {
+    name.unwrap_or("(none)")
+}
+
+fn amount_cell(amount: i64) -> String {
+    format!(
+        "<td class=\"amount{}\">{}</td>",
+        if amount < 0 { " negative" } else { "" },
+        format_amount(amount),
+    )
+}
+
+fn format_amount(amount: i64) -> String {
+    format!(
+        "{}{}.{:02} €",
+        if amount < 0 { "-" } else { "" },
+        amount.abs() / 100,
+        amount.abs() % 100,
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -1,7 +1,14 @@
+use chrono::NaiveDate;
 use handlers::Handler;
+use rufm_core::{
+    models::recurring_transactions::Frequency,
+    reports::{Month, Period, SpendingGroup},
+};
 use structopt::StructOpt;
 
+mod charts;
 mod handlers;
+mod html;
 
 #[derive(Debug, StructOpt)]
 struct Opt {
@@ -9,6 +16,11 @@ struct Opt {
     #[structopt(short, long, default_value = "~/.rufm.db")]
     database_path: String,
 
+    /// Open an existing database without writing to it or migrating it,
+    /// e.g. to run reports
+    #[structopt(long)]
+    read_only: bool,
+
     #[structopt(subcommand)]
     command: Command,
 }
@@ -19,9 +31,23 @@ enum Command {
     Accounts(AccountsCommand),
     /// Create, list, and manage transactions
     Transactions(TransactionsCommand),
-    #[cfg(feature = "import-firefly-iii")]
-    /// Import from Firefly III
-    Import(ImportCommand),
+    /// Create, list, and manage payees
+    Payees(PayeesCommand),
+    /// Create, test, and apply rules changing new transactions
+    Rules(RulesCommand),
+    /// Create and list recurring transactions
+    Recurring(RecurringCommand),
+    /// Project the balance of an account over the next days
+    Forecast(ForecastOpt),
+    /// Summarize transactions over time
+    Report(ReportCommand),
+    /// Check the consistency of the database
+    Doctor(DoctorOpt),
+    /// Show and apply the migrations of the database schema
+    Db(DbCommand),
+    #[cfg(feature = "import")]
+    /// Import from other applications and bank statements
+    Import(ImportOpt),
 }
 
 #[derive(Debug, StructOpt)]
@@ -39,6 +65,9 @@ pub struct AccountsListOpt;
 pub struct AccountsShowOpt {
     /// Account name
     name: String,
+    /// Draw the balance history of the account
+    #[structopt(long)]
+    chart: bool,
 }
 
 #[derive(Debug, StructOpt)]
@@ -61,26 +90,391 @@ pub struct TransactionsCreateOpt {
     source_account: String,
     /// Destination account name
     destination_account: String,
+    /// Free-text notes
+    #[structopt(long)]
+    notes: Option<String>,
 }
 
 pub struct TransactionsListOpt;
 
+#[derive(Debug, StructOpt)]
+pub struct TransactionsAttachOpt {
+    /// Transaction ID, as shown by `transactions list`
+    id: i32,
+    /// File to attach, such as a PDF or JPEG receipt
+    file: String,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct TransactionsAttachmentsOpt {
+    /// Transaction ID, as shown by `transactions list`
+    id: i32,
+    /// Directory to write the attached files to
+    #[structopt(long)]
+    extract: Option<String>,
+}
+
 #[derive(Debug, StructOpt)]
 pub enum TransactionsCommand {
     /// Create a transaction
     Create(TransactionsCreateOpt),
     /// List all transactions
     List,
+    /// Attach a file to a transaction
+    Attach(TransactionsAttachOpt),
+    /// List the files attached to a transaction
+    Attachments(TransactionsAttachmentsOpt),
+}
+
+#[derive(Debug, StructOpt)]
+pub struct PayeesCreateOpt {
+    /// Payee name
+    name: String,
+    /// Category of the payee's transactions which have none, created if
+    /// needed
+    #[structopt(long)]
+    category: Option<String>,
+    /// Name of the account imports book the payee's transactions on
+    #[structopt(long)]
+    account: Option<String>,
+}
+
+pub struct PayeesListOpt;
+
+#[derive(Debug, StructOpt)]
+pub struct PayeesAliasOpt {
+    /// Payee name
+    name: String,
+    /// Other name of the payee, as written by the bank
+    alias: String,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct PayeesUpdateOpt {
+    /// Payee name
+    name: String,
+    /// Category of the payee's transactions which have none, created if
+    /// needed
+    #[structopt(long)]
+    category: Option<String>,
+    /// Name of the account imports book the payee's transactions on
+    #[structopt(long)]
+    account: Option<String>,
+}
+
+#[derive(Debug, StructOpt)]
+pub enum PayeesCommand {
+    /// Create a payee
+    Create(PayeesCreateOpt),
+    /// List all payees
+    List,
+    /// Add an alias to a payee
+    Alias(PayeesAliasOpt),
+    /// Change the default category or account of a payee
+    Update(PayeesUpdateOpt),
+}
+
+#[derive(Debug, StructOpt)]
+pub struct RulesAddOpt {
+    /// Rule name
+    name: String,
+    /// Regular expression the transaction name must match
+    #[structopt(long)]
+    pattern: Option<String>,
+    /// Minimum transaction amount (in euros)
+    #[structopt(long)]
+    min_amount: Option<Money>,
+    /// Maximum transaction amount (in euros)
+    #[structopt(long)]
+    max_amount: Option<Money>,
+    /// Name of the account the transaction must come from
+    #[structopt(long)]
+    source_account: Option<String>,
+    /// New transaction name
+    #[structopt(long)]
+    rename: Option<String>,
+    /// Name of the account to send the transaction to instead
+    #[structopt(long)]
+    destination_account: Option<String>,
+    /// Category to set, created if needed
+    #[structopt(long)]
+    category: Option<String>,
+    /// Tags to add, created if needed
+    #[structopt(long = "tag")]
+    tags: Vec<String>,
+}
+
+pub struct RulesListOpt;
+
+#[derive(Debug, StructOpt)]
+pub struct RulesTestOpt {
+    /// Name of the rule to test, all rules by default
+    name: Option<String>,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct RulesApplyOpt {
+    /// Apply the rules to the existing transactions without a category, as
+    /// they are only applied to new transactions otherwise
+    #[structopt(long)]
+    retroactive: bool,
+    /// Also apply the rules to the existing transactions which already have
+    /// a category
+    #[structopt(long, requires = "retroactive")]
+    include_categorized: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub enum RulesCommand {
+    /// Add a rule, applied to the transactions created after it
+    Add(RulesAddOpt),
+    /// List all rules
+    List,
+    /// List the existing transactions matching the rules, without changing
+    /// them
+    Test(RulesTestOpt),
+    /// Apply the rules to the existing transactions, with --retroactive
+    Apply(RulesApplyOpt),
+}
+
+#[derive(Debug, StructOpt)]
+pub struct RecurringAddOpt {
+    /// Recurring transaction name
+    name: String,
+    /// Transaction amount (in euros)
+    amount: Money,
+    /// Source account name
+    source_account: String,
+    /// Destination account name
+    destination_account: String,
+    /// How often the transaction repeats: daily, weekly, monthly or yearly
+    #[structopt(long, default_value = "monthly")]
+    every: Frequency,
+    /// Date of the first occurrence (e.g. 2021-09-01), today by default
+    #[structopt(long)]
+    start: Option<NaiveDate>,
+    /// Last date an occurrence can fall on
+    #[structopt(long)]
+    end: Option<NaiveDate>,
+}
+
+pub struct RecurringListOpt;
+
+#[derive(Debug, StructOpt)]
+pub enum RecurringCommand {
+    /// Add a recurring transaction
+    Add(RecurringAddOpt),
+    /// List all recurring transactions
+    List,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct ForecastOpt {
+    /// Account name
+    account: String,
+    /// Number of days to project the balance over
+    #[structopt(long, default_value = "30")]
+    days: u32,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct ReportIncomeExpenseOpt {
+    /// First month of the report (e.g. 2021-01)
+    #[structopt(long)]
+    from: Month,
+    /// Last month of the report (e.g. 2021-12)
+    #[structopt(long)]
+    to: Month,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct ReportNetWorthOpt {
+    /// First day of the report (e.g. 2021-01-01)
+    #[structopt(long)]
+    from: NaiveDate,
+    /// Last day of the report, today by default
+    #[structopt(long)]
+    to: Option<NaiveDate>,
+    /// Length of the periods: day, week, month or year
+    #[structopt(long, default_value = "month")]
+    every: Period,
+    /// Draw the net worth as a sparkline instead of a table
+    #[structopt(long)]
+    chart: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct ReportSpendingOpt {
+    /// Month of the report (e.g. 2021-08), the current one by default
+    #[structopt(long)]
+    period: Option<Month>,
+    /// What to group spending by: account, category or payee
+    #[structopt(long, default_value = "account")]
+    by: SpendingGroup,
+    /// Draw spending as bars, followed by a calendar of daily spending
+    #[structopt(long)]
+    chart: bool,
+}
+
+#[derive(Debug, StructOpt)]
+pub struct ReportSummaryOpt {
+    /// Month of the summary (e.g. 2021-08), the current one by default
+    #[structopt(long)]
+    period: Option<Month>,
+    /// What to group spending by: account, category or payee
+    #[structopt(long, default_value = "account")]
+    by: SpendingGroup,
+    /// .html file to write the summary to
+    #[structopt(short, long)]
+    output: String,
+}
+
+#[derive(Debug, StructOpt)]
+pub enum ReportCommand {
+    /// Show the income, expenses and savings rate of each month
+    IncomeExpense(ReportIncomeExpenseOpt),
+    /// Show the combined balance of the asset accounts over time
+    NetWorth(ReportNetWorthOpt),
+    /// Show what was spent during a month, compared with the month before
+    Spending(ReportSpendingOpt),
+    /// Write the income and expenses and the net worth of the last twelve
+    /// months, and the spending of the month, to a standalone HTML file
+    Summary(ReportSummaryOpt),
+}
+
+#[derive(Debug, StructOpt)]
+pub struct DoctorOpt {
+    /// Fix the problems which can be fixed without guessing
+    #[structopt(long)]
+    fix: bool,
+}
+
+pub struct DbStatusOpt;
+
+pub struct DbMigrateOpt;
+
+#[derive(Debug, StructOpt)]
+pub enum DbCommand {
+    /// List the applied and pending migrations
+    Status,
+    /// Back up the database, then apply the pending migrations
+    Migrate,
 }
 
 #[cfg(feature = "import-firefly-iii")]
 #[derive(Debug, StructOpt)]
+pub struct ImportFireflyIiiOpt {
+    /// .csv export file path
+    #[structopt(required_unless = "url")]
+    export_file: Option<String>,
+    /// Base URL of a Firefly III instance to import from through its API
+    #[structopt(long, conflicts_with = "export-file", requires = "token")]
+    url: Option<String>,
+    /// Personal access token for the Firefly III API
+    #[structopt(long, env = "FIREFLY_III_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+    /// Import the valid records of the export file, skipping the invalid ones
+    #[structopt(long, conflicts_with = "url")]
+    skip_invalid_rows: bool,
+    /// File to write the line numbers and errors of skipped records to
+    #[structopt(long, requires = "skip-invalid-rows")]
+    rejects: Option<String>,
+    /// What the API listed, fetched before the database is locked.
+    #[structopt(skip)]
+    api_export: std::cell::OnceCell<rufm_import_firefly_iii::ApiExport>,
+}
+
+#[cfg(feature = "import-ofx")]
+#[derive(Debug, StructOpt)]
+pub struct ImportOfxOpt {
+    /// .ofx or .qfx statement file path
+    file: String,
+    /// Name of the account the statement belongs to
+    #[structopt(short, long)]
+    account: String,
+}
+
+#[cfg(feature = "import-qif")]
+#[derive(Debug, StructOpt)]
+pub struct ImportQifOpt {
+    /// .qif file path
+    file: String,
+    /// Name of the account the file belongs to
+    #[structopt(short, long)]
+    account: String,
+    /// Date format (e.g. "%d/%m/%Y"), guessed from the file by default
+    #[structopt(long)]
+    date_format: Option<String>,
+}
+
+#[cfg(feature = "import-camt053")]
+#[derive(Debug, StructOpt)]
+pub struct ImportCamt053Opt {
+    /// .xml statement file path
+    file: String,
+    /// Name of the account the statement belongs to, defaults to its IBAN
+    #[structopt(short, long)]
+    account: Option<String>,
+}
+
+#[cfg(feature = "import-csv")]
+#[derive(Debug, StructOpt)]
+pub struct ImportCsvOpt {
+    /// .csv file path
+    file: String,
+    /// .toml file describing the CSV layout
+    #[structopt(short, long)]
+    profile: String,
+    /// Name of the account the file belongs to
+    #[structopt(short, long)]
+    account: String,
+    /// Import without asking for confirmation after the preview
+    #[structopt(short, long)]
+    yes: bool,
+}
+
+#[cfg(feature = "import")]
+#[derive(Debug, StructOpt)]
+pub struct ImportOpt {
+    /// Print what would be imported without writing to the database
+    #[structopt(long)]
+    dry_run: bool,
+    /// File to import, its format detected from its content or extension
+    file: Option<String>,
+    /// Format of the file, when it cannot be detected. CSV files are only
+    /// imported with --profile
+    #[structopt(long, requires = "file")]
+    format: Option<String>,
+    /// Name of the account the file belongs to, for formats which do not
+    /// name it
+    #[structopt(short, long, requires = "file")]
+    account: Option<String>,
+    #[cfg(feature = "import-csv")]
+    /// .toml file describing the layout of a CSV file to import
+    #[structopt(long, requires = "file")]
+    profile: Option<String>,
+    #[structopt(subcommand)]
+    command: Option<ImportCommand>,
+}
+
+#[cfg(feature = "import")]
+#[derive(Debug, StructOpt)]
 pub enum ImportCommand {
+    #[cfg(feature = "import-firefly-iii")]
     /// Import from Firefly III
-    FireflyIii {
-        /// .csv export file path
-        export_file: String,
-    },
+    FireflyIii(ImportFireflyIiiOpt),
+    #[cfg(feature = "import-ofx")]
+    /// Import an OFX bank statement
+    Ofx(ImportOfxOpt),
+    #[cfg(feature = "import-qif")]
+    /// Import a QIF file
+    Qif(ImportQifOpt),
+    #[cfg(feature = "import-camt053")]
+    /// Import an ISO 20022 CAMT.053 bank statement
+    Camt053(ImportCamt053Opt),
+    #[cfg(feature = "import-csv")]
+    /// Import a bank CSV export described by a profile
+    Csv(ImportCsvOpt),
 }
 
 #[derive(Debug)]
@@ -128,7 +522,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let database_path = shellexpand::tilde(&opt.database_path);
 
-    let client = rufm_core::Client::new(Some(&database_path))?;
+    let client = rufm_core::ClientBuilder::new(&database_path)
+        .read_only(opt.read_only)
+        .create_if_missing(!opt.read_only)
+        .run_migrations(false)
+        .allow_newer_schema(matches!(opt.command, Command::Db(DbCommand::Status)))
+        .build()?;
+    if !matches!(opt.command, Command::Db(_)) {
+        handlers::db::prepare_schema(&client)?;
+    }
 
     opt.command.handle(&client)
 }
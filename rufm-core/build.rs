@@ -0,0 +1,40 @@
+//! Lists the versions of the migrations `embed_migrations!` embeds, which it
+//! does not expose.
+
+use std::{env, fs, path::Path};
+
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+
+    // Versions are the timestamps migration directories are named after,
+    // without their dashes, as diesel reads them.
+    let mut versions = fs::read_dir("migrations")
+        .expect("cannot read the migrations directory")
+        .map(|entry| entry.expect("cannot read the migrations directory"))
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let timestamp = name.split('_').next().unwrap_or_default();
+
+            timestamp.replace('-', "")
+        })
+        .collect::<Vec<_>>();
+    versions.sort();
+
+    let known_versions = format!(
+        "/// The versions of the migrations embedded in this version of rufm, \
+         oldest first, which `embed_migrations!` does not list.\n\
+         pub const KNOWN_VERSIONS: &[&str] = &[{}];\n",
+        versions
+            .iter()
+            .map(|version| format!("{:?}", version))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    fs::write(
+        Path::new(&out_dir).join("known_versions.rs"),
+        known_versions,
+    )
+    .expect("cannot write the known migration versions");
+}
@@ -0,0 +1,3 @@
+ALTER TABLE transactions ADD COLUMN external_id varchar(255);
+
+CREATE UNIQUE INDEX transactions_external_id ON transactions (external_id);
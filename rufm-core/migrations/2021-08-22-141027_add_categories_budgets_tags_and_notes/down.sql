@@ -0,0 +1,29 @@
+DROP TABLE transaction_tags;
+
+-- Columns referencing other tables cannot be dropped, so the table is rebuilt.
+CREATE TABLE transactions_without_categories (
+    id integer NOT NULL PRIMARY KEY,
+    name varchar(255) NOT NULL,
+    source_account_id int NOT NULL,
+    destination_account_id int NOT NULL,
+    amount bigint NOT NULL,
+    date DATE NOT NULL,
+    external_id varchar(255),
+
+    FOREIGN KEY (source_account_id) REFERENCES accounts (id),
+    FOREIGN KEY (destination_account_id) REFERENCES accounts (id)
+);
+
+INSERT INTO transactions_without_categories
+SELECT id, name, source_account_id, destination_account_id, amount, date, external_id
+FROM transactions;
+
+DROP TABLE transactions;
+
+ALTER TABLE transactions_without_categories RENAME TO transactions;
+
+CREATE UNIQUE INDEX transactions_external_id ON transactions (external_id);
+
+DROP TABLE tags;
+DROP TABLE budgets;
+DROP TABLE categories;
@@ -0,0 +1,27 @@
+CREATE TABLE categories (
+    id integer NOT NULL PRIMARY KEY,
+    name varchar(255) NOT NULL UNIQUE
+);
+
+CREATE TABLE budgets (
+    id integer NOT NULL PRIMARY KEY,
+    name varchar(255) NOT NULL UNIQUE
+);
+
+CREATE TABLE tags (
+    id integer NOT NULL PRIMARY KEY,
+    name varchar(255) NOT NULL UNIQUE
+);
+
+ALTER TABLE transactions ADD COLUMN category_id int REFERENCES categories (id);
+ALTER TABLE transactions ADD COLUMN budget_id int REFERENCES budgets (id);
+ALTER TABLE transactions ADD COLUMN notes text;
+
+CREATE TABLE transaction_tags (
+    transaction_id int NOT NULL,
+    tag_id int NOT NULL,
+
+    PRIMARY KEY (transaction_id, tag_id),
+    FOREIGN KEY (transaction_id) REFERENCES transactions (id),
+    FOREIGN KEY (tag_id) REFERENCES tags (id)
+);
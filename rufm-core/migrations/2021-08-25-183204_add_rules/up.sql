@@ -0,0 +1,24 @@
+CREATE TABLE rules (
+    id integer NOT NULL PRIMARY KEY,
+    name varchar(255) NOT NULL UNIQUE,
+    name_pattern text,
+    min_amount bigint,
+    max_amount bigint,
+    source_account_id int,
+    set_name varchar(255),
+    set_destination_account_id int,
+    set_category_id int,
+
+    FOREIGN KEY (source_account_id) REFERENCES accounts (id),
+    FOREIGN KEY (set_destination_account_id) REFERENCES accounts (id),
+    FOREIGN KEY (set_category_id) REFERENCES categories (id)
+);
+
+CREATE TABLE rule_tags (
+    rule_id int NOT NULL,
+    tag_id int NOT NULL,
+
+    PRIMARY KEY (rule_id, tag_id),
+    FOREIGN KEY (rule_id) REFERENCES rules (id),
+    FOREIGN KEY (tag_id) REFERENCES tags (id)
+);
@@ -0,0 +1,32 @@
+-- Columns referencing other tables cannot be dropped, so the table is rebuilt.
+CREATE TABLE transactions_without_payees (
+    id integer NOT NULL PRIMARY KEY,
+    name varchar(255) NOT NULL,
+    source_account_id int NOT NULL,
+    destination_account_id int NOT NULL,
+    amount bigint NOT NULL,
+    date DATE NOT NULL,
+    external_id varchar(255),
+    category_id int,
+    budget_id int,
+    notes text,
+
+    FOREIGN KEY (source_account_id) REFERENCES accounts (id),
+    FOREIGN KEY (destination_account_id) REFERENCES accounts (id),
+    FOREIGN KEY (category_id) REFERENCES categories (id),
+    FOREIGN KEY (budget_id) REFERENCES budgets (id)
+);
+
+INSERT INTO transactions_without_payees
+SELECT id, name, source_account_id, destination_account_id, amount, date, external_id,
+    category_id, budget_id, notes
+FROM transactions;
+
+DROP TABLE transactions;
+
+ALTER TABLE transactions_without_payees RENAME TO transactions;
+
+CREATE UNIQUE INDEX transactions_external_id ON transactions (external_id);
+
+DROP TABLE payee_aliases;
+DROP TABLE payees;
@@ -0,0 +1,18 @@
+CREATE TABLE payees (
+    id integer NOT NULL PRIMARY KEY,
+    name varchar(255) NOT NULL UNIQUE COLLATE NOCASE,
+    default_category_id int,
+    default_account_id int,
+
+    FOREIGN KEY (default_category_id) REFERENCES categories (id),
+    FOREIGN KEY (default_account_id) REFERENCES accounts (id)
+);
+
+CREATE TABLE payee_aliases (
+    alias varchar(255) NOT NULL PRIMARY KEY COLLATE NOCASE,
+    payee_id int NOT NULL,
+
+    FOREIGN KEY (payee_id) REFERENCES payees (id)
+);
+
+ALTER TABLE transactions ADD COLUMN payee_id int REFERENCES payees (id);
@@ -0,0 +1,9 @@
+CREATE TABLE attachments (
+    id integer NOT NULL PRIMARY KEY,
+    transaction_id int NOT NULL,
+    file_name varchar(255) NOT NULL,
+    content_type varchar(255) NOT NULL,
+    content blob NOT NULL,
+
+    FOREIGN KEY (transaction_id) REFERENCES transactions (id)
+);
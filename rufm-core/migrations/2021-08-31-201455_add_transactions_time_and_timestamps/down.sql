@@ -0,0 +1,6 @@
+DROP INDEX transactions_order;
+
+ALTER TABLE transactions DROP COLUMN updated_at;
+ALTER TABLE transactions DROP COLUMN created_at;
+ALTER TABLE transactions DROP COLUMN utc_offset;
+ALTER TABLE transactions DROP COLUMN time;
@@ -0,0 +1,10 @@
+ALTER TABLE transactions ADD COLUMN time TIME;
+ALTER TABLE transactions ADD COLUMN utc_offset int;
+-- Columns cannot be added with a non-constant default, so rufm sets both
+-- timestamps itself.
+ALTER TABLE transactions ADD COLUMN created_at timestamp NOT NULL DEFAULT '1970-01-01 00:00:00';
+ALTER TABLE transactions ADD COLUMN updated_at timestamp NOT NULL DEFAULT '1970-01-01 00:00:00';
+
+UPDATE transactions SET created_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP;
+
+CREATE INDEX transactions_order ON transactions (date, time, id);
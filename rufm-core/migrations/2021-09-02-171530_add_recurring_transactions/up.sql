@@ -0,0 +1,13 @@
+CREATE TABLE recurring_transactions (
+    id integer NOT NULL PRIMARY KEY,
+    name varchar(255) NOT NULL,
+    source_account_id int NOT NULL,
+    destination_account_id int NOT NULL,
+    amount bigint NOT NULL,
+    frequency int NOT NULL,
+    start_date date NOT NULL,
+    end_date date,
+
+    FOREIGN KEY (source_account_id) REFERENCES accounts (id),
+    FOREIGN KEY (destination_account_id) REFERENCES accounts (id)
+);
@@ -0,0 +1,171 @@
+use std::{cell::RefCell, path::Path, time::Duration};
+
+use diesel::{Connection, SqliteConnection};
+
+use crate::{migrations::MigrationError, Client, ClientCreationError};
+
+/// Options to open a `Client` with.
+///
+/// By default, the database file is created if missing, its schema is
+/// migrated, after backing it up, and foreign keys are enforced. Databases
+/// migrated by a newer version of rufm are refused. Database files use a
+/// write-ahead log and wait up to 5 seconds for the other writers, so that
+/// several processes can share them.
+#[derive(Debug, Clone)]
+pub struct ClientBuilder {
+    path: Option<String>,
+    read_only: bool,
+    create_if_missing: bool,
+    wal: bool,
+    busy_timeout: Option<Duration>,
+    foreign_keys: bool,
+    run_migrations: bool,
+    allow_newer_schema: bool,
+}
+
+impl ClientBuilder {
+    /// Opens the database file at `path`.
+    pub fn new(path: &str) -> ClientBuilder {
+        ClientBuilder {
+            path: Some(path.to_string()),
+            wal: true,
+            busy_timeout: Some(Duration::from_secs(5)),
+            ..ClientBuilder::in_memory()
+        }
+    }
+
+    /// Opens a database which lives in memory, and is lost when the client is
+    /// dropped.
+    pub fn in_memory() -> ClientBuilder {
+        ClientBuilder {
+            path: None,
+            read_only: false,
+            create_if_missing: true,
+            wal: false,
+            busy_timeout: None,
+            foreign_keys: true,
+            run_migrations: true,
+            allow_newer_schema: false,
+        }
+    }
+
+    /// Opens the database file read-only, so that every write fails. Its
+    /// migrations cannot be run either, so opening it fails when some are
+    /// pending, unless they are not run anyway.
+    pub fn read_only(mut self, read_only: bool) -> ClientBuilder {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Whether to create the database file if it does not exist, or to fail.
+    pub fn create_if_missing(mut self, create_if_missing: bool) -> ClientBuilder {
+        self.create_if_missing = create_if_missing;
+        self
+    }
+
+    /// Uses a write-ahead log, which lets readers work while a writer does.
+    /// The mode is kept by the database file.
+    pub fn wal(mut self, wal: bool) -> ClientBuilder {
+        self.wal = wal;
+        self
+    }
+
+    /// How long to wait for another connection to release its lock before
+    /// failing with a "database is locked" error.
+    pub fn busy_timeout(mut self, busy_timeout: Duration) -> ClientBuilder {
+        self.busy_timeout = Some(busy_timeout);
+        self
+    }
+
+    /// Whether to enforce the references between tables, which SQLite only
+    /// does when asked to on each connection.
+    pub fn foreign_keys(mut self, foreign_keys: bool) -> ClientBuilder {
+        self.foreign_keys = foreign_keys;
+        self
+    }
+
+    /// Whether to bring the schema up to date when opening the database, as
+    /// `Client::migrate` does.
+    pub fn run_migrations(mut self, run_migrations: bool) -> ClientBuilder {
+        self.run_migrations = run_migrations;
+        self
+    }
+
+    /// Opens databases migrated by a newer version of rufm, whose schema may
+    /// not be understood, e.g. to show their migrations.
+    pub fn allow_newer_schema(mut self, allow_newer_schema: bool) -> ClientBuilder {
+        self.allow_newer_schema = allow_newer_schema;
+        self
+    }
+
+    pub fn build(self) -> Result<Client, ClientCreationError> {
+        let database_url = match &self.path {
+            Some(path) if !self.create_if_missing && !Path::new(path).exists() => {
+                return Err(ClientCreationError::DatabaseNotFound(path.clone()))
+            }
+            Some(path) if self.read_only => read_only_uri(path),
+            Some(path) => path.clone(),
+            None => ":memory:".to_string(),
+        };
+        let conn = SqliteConnection::establish(&database_url)?;
+
+        if let Some(busy_timeout) = self.busy_timeout {
+            conn.execute(&format!(
+                "PRAGMA busy_timeout = {}",
+                busy_timeout.as_millis()
+            ))?;
+        }
+        conn.execute(&format!(
+            "PRAGMA foreign_keys = {}",
+            if self.foreign_keys { "ON" } else { "OFF" }
+        ))?;
+
+        if self.read_only && self.path.is_none() {
+            // There is no file to open read-only.
+            conn.execute("PRAGMA query_only = ON")?;
+        }
+
+        let client = Client {
+            conn,
+            path: self.path,
+            compiled_rules: RefCell::new(None),
+        };
+        if !self.allow_newer_schema && client.path.is_some() {
+            let unknown = client.migration_status()?.unknown;
+            if !unknown.is_empty() {
+                return Err(MigrationError::NewerSchema(unknown).into());
+            }
+        }
+        // Only once the schema is known to be understood, as a newer version
+        // of rufm may not expect the journal mode to change.
+        if self.wal && !self.read_only {
+            client.conn.execute("PRAGMA journal_mode = WAL")?;
+        }
+        if self.run_migrations && self.read_only {
+            let pending = client.migration_status()?.pending;
+            if !pending.is_empty() {
+                return Err(MigrationError::PendingMigrations(pending).into());
+            }
+        } else if self.run_migrations {
+            client.migrate()?;
+        }
+
+        Ok(client)
+    }
+}
+
+/// The URI opening the database file at `path` read-only, even when the file
+/// could be written to.
+fn read_only_uri(path: &str) -> String {
+    let mut uri = String::from("file:");
+    for c in path.chars() {
+        match c {
+            // Characters which would end the path, or be decoded.
+            '?' | '#' | '%' => uri.push_str(&format!("%{:02X}", c as u32)),
+            c => uri.push(c),
+        }
+    }
+    uri.push_str("?mode=ro");
+
+    uri
+}
@@ -0,0 +1,17 @@
+//! Projected balances of accounts.
+
+use chrono::NaiveDate;
+
+/// Balance of an account at the end of a day.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DailyBalance {
+    pub date: NaiveDate,
+    pub balance: i64,
+}
+
+/// The first day ending with a negative balance, if any.
+pub fn first_negative_day(balances: &[DailyBalance]) -> Option<&DailyBalance> {
+    balances
+        .iter()
+        .find(|daily_balance| daily_balance.balance < 0)
+}
@@ -0,0 +1,418 @@
+//! Types shared by the importers.
+
+use std::{fmt, io::Read, path::Path};
+
+use thiserror::Error;
+
+use crate::{
+    models::{
+        accounts::{Account, AccountType, NewAccount},
+        payees::Payee,
+        transactions::Transaction,
+    },
+    AccountsRepository,
+    Client,
+    OptionalExtension,
+    QueryError,
+};
+
+/// A file format rufm can import.
+pub trait Importer {
+    /// Short name of the format, e.g. `"ofx"`, used to choose it explicitly.
+    fn name(&self) -> &'static str;
+
+    /// File extensions of the format, without the leading dot.
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Whether `head`, the first bytes of a file, looks like this format.
+    fn sniff(&self, head: &[u8]) -> bool;
+
+    /// Imports `rdr` into `account`, for the formats which do not name the
+    /// account they belong to.
+    fn import(
+        &self,
+        client: &Client,
+        rdr: &mut dyn Read,
+        account: Option<&str>,
+    ) -> Result<ImportReport, Box<dyn std::error::Error>>;
+}
+
+/// The importers a file's format is detected among.
+///
+/// Each importer crate has a `register` function adding its importers, so
+/// that it decides which ones it provides.
+#[derive(Default)]
+pub struct ImporterRegistry {
+    importers: Vec<Box<dyn Importer>>,
+}
+
+impl ImporterRegistry {
+    pub fn register<I: Importer + 'static>(&mut self, importer: I) {
+        self.importers.push(Box::new(importer));
+    }
+
+    pub fn importers(&self) -> impl Iterator<Item = &dyn Importer> {
+        self.importers.iter().map(|importer| importer.as_ref())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Importer> {
+        self.importers().find(|importer| importer.name() == name)
+    }
+
+    /// Finds the importer recognizing `head`, the first bytes of a file,
+    /// trying the importers of its extension first.
+    pub fn detect(&self, path: &Path, head: &[u8]) -> Option<&dyn Importer> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_lowercase())
+            .unwrap_or_default();
+        let (mut candidates, others): (Vec<_>, Vec<_>) = self
+            .importers()
+            .partition(|importer| importer.extensions().contains(&extension.as_str()));
+        candidates.extend(others);
+
+        candidates.into_iter().find(|importer| importer.sniff(head))
+    }
+}
+
+/// Errors of `get_or_create_payee_account`.
+#[derive(Error, Debug)]
+pub enum PayeeAccountError {
+    #[error("{0}")]
+    AccountTypeMismatch(#[from] AccountTypeMismatch),
+    #[error("database error: {0}")]
+    DatabaseError(#[from] QueryError),
+}
+
+/// An existing account an import would book on, whose type is not the one
+/// the imported transaction needs.
+#[derive(Error, Debug)]
+#[error("account '{name}' is of type {actual:?}, where type {expected:?} is needed")]
+pub struct AccountTypeMismatch {
+    pub name: String,
+    pub actual: AccountType,
+    pub expected: AccountType,
+}
+
+/// The account of `payee` as the counterparty of an imported transaction:
+/// its default account if it has `account_type`, or else the account named
+/// after it, which is created with `account_type` if needed. The
+/// counterparty names of a payee, such as those of its card payments, thus
+/// share an account.
+///
+/// An account named after the payee with another type, such as an Asset
+/// account or the Expense account of a payee seen on a deposit, is not used:
+/// an `AccountTypeMismatch` is returned instead.
+pub fn get_or_create_payee_account(
+    client: &Client,
+    report: &mut ImportReport,
+    payee: &Payee,
+    account_type: AccountType,
+) -> Result<Account, PayeeAccountError> {
+    if let Some(account_id) = payee.default_account_id {
+        let account = client.get_account_by_id(&account_id)?;
+        if account.account_type == account_type {
+            return Ok(account);
+        }
+    }
+
+    match client.get_account_by_name(&payee.name).optional()? {
+        Some(account) if account.account_type == account_type => Ok(account),
+        Some(account) => Err(AccountTypeMismatch {
+            name: account.name,
+            actual: account.account_type,
+            expected: account_type,
+        }
+        .into()),
+        None => {
+            let account = client.create_account(&NewAccount {
+                name: &payee.name,
+                account_type,
+                initial_balance: 0,
+            })?;
+            report.add_account(&account);
+
+            Ok(account)
+        }
+    }
+}
+
+/// What an import did, or would do in a dry run.
+///
+/// Importers list the records they cannot import in `errors` and go on with
+/// the others, unless they are told to fail on the first one. Callers decide
+/// whether to keep the others: `rufm import` rolls the whole import back when
+/// its report has errors, unless invalid rows are skipped.
+///
+/// A sampled report only lists the first entries of each kind, and counts
+/// the others, so that its size does not grow with the imported file.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub created_accounts: Vec<CreatedAccount>,
+    pub created_transactions: Vec<CreatedTransaction>,
+    pub initial_balances: Vec<InitialBalanceChange>,
+    /// External IDs of the records which were already imported.
+    pub skipped_duplicates: Vec<String>,
+    /// Records which could not be imported. They are always all listed.
+    pub errors: Vec<RowError>,
+    /// Data which was imported only partially, such as fields rufm has no
+    /// equivalent for.
+    pub warnings: Vec<String>,
+    /// Number of entries of each kind, including those a sampled report
+    /// leaves out.
+    pub counts: ImportCounts,
+    sample_size: Option<usize>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct ImportCounts {
+    pub created_accounts: usize,
+    pub created_transactions: usize,
+    pub initial_balances: usize,
+    pub skipped_duplicates: usize,
+    pub warnings: usize,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CreatedAccount {
+    pub name: String,
+    pub account_type: AccountType,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CreatedTransaction {
+    pub name: String,
+    pub amount: i64,
+    pub date: chrono::NaiveDate,
+    pub source_account: String,
+    pub destination_account: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct InitialBalanceChange {
+    pub account: String,
+    pub initial_balance: i64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RowError {
+    /// Line of the record in the imported file, when there is one.
+    pub line: Option<u64>,
+    /// The importer's error message, which usually mentions the line too.
+    pub message: String,
+}
+
+impl ImportReport {
+    /// A report listing at most `sample_size` entries of each kind but
+    /// errors.
+    pub fn sampled(sample_size: usize) -> ImportReport {
+        ImportReport {
+            sample_size: Some(sample_size),
+            ..ImportReport::default()
+        }
+    }
+
+    pub fn add_account(&mut self, account: &Account) {
+        self.counts.created_accounts += 1;
+        if self.has_room(self.created_accounts.len()) {
+            self.created_accounts.push(CreatedAccount {
+                name: account.name.clone(),
+                account_type: account.account_type,
+            });
+        }
+    }
+
+    pub fn add_transaction(
+        &mut self,
+        transaction: &Transaction,
+        source_account: &Account,
+        destination_account: &Account,
+    ) {
+        self.counts.created_transactions += 1;
+        if self.has_room(self.created_transactions.len()) {
+            self.created_transactions.push(CreatedTransaction {
+                name: transaction.name.clone(),
+                amount: transaction.amount,
+                date: transaction.date,
+                source_account: source_account.name.clone(),
+                destination_account: destination_account.name.clone(),
+            });
+        }
+    }
+
+    pub fn add_initial_balance(&mut self, account: &Account) {
+        self.counts.initial_balances += 1;
+        if self.has_room(self.initial_balances.len()) {
+            self.initial_balances.push(InitialBalanceChange {
+                account: account.name.clone(),
+                initial_balance: account.initial_balance,
+            });
+        }
+    }
+
+    pub fn add_duplicate(&mut self, external_id: &str) {
+        self.counts.skipped_duplicates += 1;
+        if self.has_room(self.skipped_duplicates.len()) {
+            self.skipped_duplicates.push(external_id.to_string());
+        }
+    }
+
+    pub fn add_error<E: fmt::Display>(&mut self, line: Option<u64>, error: E) {
+        self.errors.push(RowError {
+            line,
+            message: error.to_string(),
+        });
+    }
+
+    pub fn add_warning(&mut self, warning: String) {
+        self.counts.warnings += 1;
+        if self.has_room(self.warnings.len()) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Adds the entries of `other`, e.g. the report of a single record once
+    /// it is imported.
+    pub fn append(&mut self, other: ImportReport) {
+        let sample_size = self.sample_size;
+        append_sample(
+            &mut self.created_accounts,
+            other.created_accounts,
+            sample_size,
+        );
+        append_sample(
+            &mut self.created_transactions,
+            other.created_transactions,
+            sample_size,
+        );
+        append_sample(
+            &mut self.initial_balances,
+            other.initial_balances,
+            sample_size,
+        );
+        append_sample(
+            &mut self.skipped_duplicates,
+            other.skipped_duplicates,
+            sample_size,
+        );
+        append_sample(&mut self.warnings, other.warnings, sample_size);
+        self.errors.extend(other.errors);
+
+        self.counts.created_accounts += other.counts.created_accounts;
+        self.counts.created_transactions += other.counts.created_transactions;
+        self.counts.initial_balances += other.counts.initial_balances;
+        self.counts.skipped_duplicates += other.counts.skipped_duplicates;
+        self.counts.warnings += other.counts.warnings;
+    }
+
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    fn has_room(&self, listed: usize) -> bool {
+        match self.sample_size {
+            Some(sample_size) => listed < sample_size,
+            None => true,
+        }
+    }
+}
+
+fn append_sample<T>(list: &mut Vec<T>, other: Vec<T>, sample_size: Option<usize>) {
+    let room = match sample_size {
+        Some(sample_size) => sample_size.saturating_sub(list.len()),
+        None => other.len(),
+    };
+    list.extend(other.into_iter().take(room));
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.created_accounts.is_empty() {
+            writeln!(f, "Accounts:")?;
+            for account in &self.created_accounts {
+                writeln!(f, "  {} ({:?})", account.name, account.account_type)?;
+            }
+            write_left_out(f, self.created_accounts.len(), self.counts.created_accounts)?;
+        }
+        if !self.initial_balances.is_empty() {
+            writeln!(f, "Initial balances:")?;
+            for initial_balance in &self.initial_balances {
+                writeln!(
+                    f,
+                    "  {}: {}",
+                    initial_balance.account, initial_balance.initial_balance
+                )?;
+            }
+            write_left_out(f, self.initial_balances.len(), self.counts.initial_balances)?;
+        }
+        if !self.created_transactions.is_empty() {
+            writeln!(f, "Transactions:")?;
+            for transaction in &self.created_transactions {
+                writeln!(
+                    f,
+                    "  {} '{}' ({}) from '{}' to '{}'",
+                    transaction.date,
+                    transaction.name,
+                    transaction.amount,
+                    transaction.source_account,
+                    transaction.destination_account
+                )?;
+            }
+            write_left_out(
+                f,
+                self.created_transactions.len(),
+                self.counts.created_transactions,
+            )?;
+        }
+        if !self.skipped_duplicates.is_empty() {
+            writeln!(f, "Skipped duplicates:")?;
+            for external_id in &self.skipped_duplicates {
+                writeln!(f, "  {}", external_id)?;
+            }
+            write_left_out(
+                f,
+                self.skipped_duplicates.len(),
+                self.counts.skipped_duplicates,
+            )?;
+        }
+        if !self.warnings.is_empty() {
+            writeln!(f, "Warnings:")?;
+            for warning in &self.warnings {
+                writeln!(f, "  {}", warning)?;
+            }
+            write_left_out(f, self.warnings.len(), self.counts.warnings)?;
+        }
+        if !self.errors.is_empty() {
+            writeln!(f, "Errors:")?;
+            for error in &self.errors {
+                writeln!(f, "  {}", error)?;
+            }
+        }
+
+        write!(
+            f,
+            "{} accounts, {} initial balances, {} transactions, {} duplicates, {} errors",
+            self.counts.created_accounts,
+            self.counts.initial_balances,
+            self.counts.created_transactions,
+            self.counts.skipped_duplicates,
+            self.errors.len()
+        )
+    }
+}
+
+/// Mentions the entries a sampled report leaves out of a list.
+fn write_left_out(f: &mut fmt::Formatter, listed: usize, count: usize) -> fmt::Result {
+    if count > listed {
+        writeln!(f, "  ... and {} more", count - listed)?;
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
@@ -0,0 +1,127 @@
+//! Checks of the consistency of the ledger, for databases written before the
+//! constraints were enforced, or by other tools.
+
+use std::fmt;
+
+use diesel::sql_types::Integer;
+
+use crate::models::{accounts::AccountId, transactions::TransactionId};
+
+/// The columns referring to accounts, by table, and whether they can be left
+/// empty.
+pub(crate) const ACCOUNT_REFERENCES: &[(&str, &str, bool)] = &[
+    ("transactions", "source_account_id", false),
+    ("transactions", "destination_account_id", false),
+    ("recurring_transactions", "source_account_id", false),
+    ("recurring_transactions", "destination_account_id", false),
+    ("rules", "source_account_id", true),
+    ("rules", "set_destination_account_id", true),
+    ("payees", "default_account_id", true),
+];
+
+#[derive(QueryableByName, Debug)]
+pub(crate) struct AccountReference {
+    #[sql_type = "Integer"]
+    pub row_id: i32,
+    #[sql_type = "Integer"]
+    pub account_id: i32,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Problem {
+    /// A row of `table` refers to an account which does not exist.
+    DanglingAccountReference {
+        table: &'static str,
+        column: &'static str,
+        row_id: i32,
+        account_id: AccountId,
+    },
+    /// A transaction from an account to itself, which changes no balance.
+    SameSourceAndDestination { transaction_id: TransactionId },
+    /// A transaction of a zero or negative amount. Negative ones can be
+    /// fixed by swapping their accounts, once both exist.
+    NonPositiveAmount {
+        transaction_id: TransactionId,
+        amount: i64,
+    },
+    /// An account whose type is not a known `AccountType`, which makes
+    /// listing accounts fail.
+    InvalidAccountType {
+        account_id: AccountId,
+        account_type: i32,
+    },
+    /// Accounts sharing a name, of which only the first can be found by
+    /// name. The others can be fixed by adding their ID to their name.
+    DuplicateAccountName {
+        name: String,
+        account_ids: Vec<AccountId>,
+    },
+}
+
+impl Problem {
+    /// Whether `IntegrityRepository::fix_problem` can fix the problem without
+    /// guessing.
+    pub fn is_fixable(&self) -> bool {
+        match self {
+            Problem::DanglingAccountReference { table, column, .. } => {
+                is_nullable_account_reference(table, column)
+            }
+            Problem::NonPositiveAmount { amount, .. } => *amount < 0,
+            Problem::DuplicateAccountName { .. } => true,
+            Problem::SameSourceAndDestination { .. } | Problem::InvalidAccountType { .. } => false,
+        }
+    }
+}
+
+fn is_nullable_account_reference(table: &str, column: &str) -> bool {
+    ACCOUNT_REFERENCES.contains(&(table, column, true))
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::DanglingAccountReference {
+                table,
+                column,
+                row_id,
+                account_id,
+            } => write!(
+                f,
+                "{} #{} refers to missing account #{} in {}",
+                table, row_id, account_id.0, column
+            ),
+            Problem::SameSourceAndDestination { transaction_id } => write!(
+                f,
+                "transaction #{} has the same source and destination account",
+                transaction_id.0
+            ),
+            Problem::NonPositiveAmount {
+                transaction_id,
+                amount,
+            } => write!(
+                f,
+                "transaction #{} has a non-positive amount ({})",
+                transaction_id.0, amount
+            ),
+            Problem::InvalidAccountType {
+                account_id,
+                account_type,
+            } => write!(
+                f,
+                "account #{} has an invalid type ({})",
+                account_id.0, account_type
+            ),
+            Problem::DuplicateAccountName { name, account_ids } => write!(
+                f,
+                "{} accounts are named '{}' (#{})",
+                account_ids.len(),
+                name,
+                account_ids
+                    .iter()
+                    .map(|account_id| account_id.0.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", #")
+            ),
+        }
+    }
+}
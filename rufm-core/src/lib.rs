@@ -8,14 +8,60 @@ extern crate chrono;
 
 embed_migrations!();
 
+mod builder;
+pub mod forecast;
+pub mod import;
+pub mod integrity;
+mod lock;
+pub mod migrations;
 pub mod models;
+pub mod reports;
 pub mod schema;
 
-use diesel::{dsl::*, prelude::*, sqlite::SqliteConnection};
-use models::{accounts::*, transactions::*};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    convert::TryFrom,
+    sync::Arc,
+};
+
+pub use builder::ClientBuilder;
+use chrono::{Datelike, Duration};
+use diesel::{connection::TransactionManager, dsl::*, prelude::*, sqlite::SqliteConnection};
+use forecast::DailyBalance;
+use integrity::{AccountReference, Problem};
+pub use lock::Lock;
+use migrations::{MigrationError, MigrationReport, MigrationStatus};
+use models::{
+    accounts::*,
+    attachments::*,
+    budgets::*,
+    categories::*,
+    payees::*,
+    recurring_transactions::*,
+    rules::*,
+    tags::*,
+    transactions::*,
+};
+use reports::{
+    DailySpending,
+    IncomeExpense,
+    Month,
+    NetWorth,
+    Period,
+    Spending,
+    SpendingGroup,
+    SpendingReport,
+};
 
 pub struct Client {
     conn: SqliteConnection,
+    /// Path of the database file, `None` in memory.
+    path: Option<String>,
+    /// The rules applied to new transactions, loaded on first use and
+    /// forgotten when they change or a transaction is rolled back. Rules
+    /// created by other clients meanwhile are not applied.
+    compiled_rules: RefCell<Option<Arc<CompiledRules>>>,
 }
 
 use thiserror::Error;
@@ -24,17 +70,216 @@ use thiserror::Error;
 pub enum ClientCreationError {
     #[error("Database connection error: {0}")]
     DatabaseConnectionError(#[from] diesel::result::ConnectionError),
-    #[error("Cannot run migrations: {0}")]
-    MigrationsError(#[from] diesel_migrations::RunMigrationsError),
+    #[error("{0}")]
+    MigrationsError(#[from] MigrationError),
+    #[error("Cannot configure the database: {0}")]
+    ConfigurationError(#[from] QueryError),
+    #[error("Database file not found: {0}")]
+    DatabaseNotFound(String),
 }
 
 impl Client {
+    /// Opens `file`, or an in-memory database without one, with the default
+    /// options of `ClientBuilder`.
     pub fn new(file: Option<&str>) -> Result<Client, ClientCreationError> {
-        let conn = SqliteConnection::establish(file.unwrap_or(":memory:"))?;
+        match file {
+            Some(file) => ClientBuilder::new(file),
+            None => ClientBuilder::in_memory(),
+        }
+        .build()
+    }
+
+    /// Runs `f` in a database transaction, which is rolled back if `f`
+    /// fails.
+    ///
+    /// The database is locked for writing when the outermost transaction
+    /// begins, so that it waits for the other writers up to the busy timeout,
+    /// where taking the lock on the first write would fail at once.
+    pub fn transaction<T, E, F>(&self, f: F) -> Result<T, E>
+    where
+        F: FnOnce() -> Result<T, E>,
+        E: From<QueryError>,
+    {
+        let transaction_manager = self.conn.transaction_manager();
+        let result =
+            if TransactionManager::<SqliteConnection>::get_transaction_depth(transaction_manager)
+                == 0
+            {
+                self.conn.immediate_transaction(f)
+            } else {
+                self.conn.transaction(f)
+            };
+        if result.is_err() {
+            self.forget_compiled_rules();
+        }
+
+        result
+    }
+
+    /// Runs `f` again, after a growing delay, while it fails because another
+    /// connection keeps the database locked for longer than the busy
+    /// timeout.
+    ///
+    /// `f` must be safe to run several times, such as a `transaction` without
+    /// other side effects.
+    pub fn retry_if_busy<T, E, F>(&self, mut f: F) -> Result<T, E>
+    where
+        F: FnMut() -> Result<T, E>,
+        E: BusyError,
+    {
+        let mut delay = std::time::Duration::from_millis(10);
+        for _ in 1..RETRY_ATTEMPTS {
+            match f() {
+                Err(error) if error.is_busy() => {
+                    // Some jitter, so that the retrying writers do not all
+                    // wake up at once.
+                    let jitter = std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map(|elapsed| elapsed.subsec_nanos() % 1_000_000)
+                        .unwrap_or(0);
+                    std::thread::sleep(delay + std::time::Duration::from_nanos(jitter.into()));
+                    delay *= 2;
+                }
+                result => return result,
+            }
+        }
+
+        f()
+    }
+
+    /// Waits for the advisory lock `name` of the database, e.g. `"import"`,
+    /// so that multi-step operations of several processes do not interleave.
+    pub fn lock(&self, name: &str) -> std::io::Result<Lock> {
+        match &self.path {
+            Some(path) => Lock::acquire(&format!("{}.{}.lock", path, name)),
+            None => Ok(Lock::in_memory()),
+        }
+    }
+
+    /// The migrations applied to the database and those left to apply.
+    pub fn migration_status(&self) -> Result<MigrationStatus, MigrationError> {
+        migrations::migration_status(&self.conn)
+    }
+
+    /// Applies the pending migrations, after copying a database file which
+    /// is not new next to it.
+    ///
+    /// Databases migrated by a newer version of rufm are left untouched.
+    pub fn migrate(&self) -> Result<MigrationReport, MigrationError> {
+        let status = self.migration_status()?;
+        if !status.unknown.is_empty() {
+            return Err(MigrationError::NewerSchema(status.unknown));
+        }
+        if status.pending.is_empty() {
+            return Ok(MigrationReport {
+                migrations: Vec::new(),
+                backup_path: None,
+            });
+        }
+
+        let backup_path = match &self.path {
+            Some(path) if !status.is_empty() => {
+                // `VACUUM INTO` refuses to overwrite a backup taken in the same
+                // second.
+                let timestamp = chrono::Local::now().format("%Y%m%d%H%M%S").to_string();
+                let backup_path = (0..)
+                    .map(|n| match n {
+                        0 => format!("{}.{}.bak", path, timestamp),
+                        n => format!("{}.{}-{}.bak", path, timestamp, n),
+                    })
+                    .find(|backup_path| !std::path::Path::new(backup_path).exists())
+                    .unwrap();
+                self.conn.execute(&format!(
+                    "VACUUM INTO '{}'",
+                    backup_path.replace('\'', "''")
+                ))?;
+
+                Some(backup_path)
+            }
+            _ => None,
+        };
+        // Only one of the processes opening a database at once migrates it.
+        let migrations = self.transaction::<_, MigrationError, _>(|| {
+            let pending = self.migration_status()?.pending;
+            embedded_migrations::run(&self.conn)?;
+
+            Ok(pending)
+        })?;
+
+        Ok(MigrationReport {
+            migrations,
+            backup_path,
+        })
+    }
+
+    /// Runs `f` in a database transaction which is always rolled back, so
+    /// that `f` can see its own changes without persisting them.
+    pub fn dry_run<T, E, F>(&self, f: F) -> Result<T, E>
+    where
+        F: FnOnce() -> Result<T, E>,
+        E: From<QueryError>,
+    {
+        let mut result = None;
+        let rollback = self.conn.transaction::<(), QueryError, _>(|| {
+            result = Some(f());
 
-        embedded_migrations::run(&conn)?;
+            Err(QueryError::RollbackTransaction)
+        });
+        self.forget_compiled_rules();
 
-        Ok(Client { conn })
+        match (result, rollback) {
+            (Some(result), Err(QueryError::RollbackTransaction)) => result,
+            (_, Err(error)) => Err(error.into()),
+            (_, Ok(())) => unreachable!("dry runs are always rolled back"),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum RuleError {
+    #[error("Invalid name pattern: {0}")]
+    InvalidNamePattern(#[from] regex::Error),
+    #[error("Database error: {0}")]
+    QueryError(#[from] QueryError),
+}
+
+/// Attempts of `Client::retry_if_busy`. The delays between them add up to
+/// about 5 seconds, on top of the busy timeout each attempt waits for: about
+/// 55 seconds in total with the default timeout of 5 seconds.
+const RETRY_ATTEMPTS: u32 = 10;
+
+/// Errors `Client::retry_if_busy` can tell to come from a locked database.
+pub trait BusyError {
+    fn is_busy(&self) -> bool;
+}
+
+impl BusyError for QueryError {
+    fn is_busy(&self) -> bool {
+        is_busy(self)
+    }
+}
+
+/// Whether a `QueryError` locking the database caused the error, e.g. in the
+/// error of an importer.
+impl BusyError for Box<dyn std::error::Error> {
+    fn is_busy(&self) -> bool {
+        std::iter::successors(Some(self.as_ref()), |error| error.source())
+            .filter_map(|error| error.downcast_ref::<QueryError>())
+            .any(is_busy)
+    }
+}
+
+/// Whether `error` comes from another connection holding a lock on the
+/// database.
+pub fn is_busy(error: &QueryError) -> bool {
+    match error {
+        QueryError::DatabaseError(_, information) => {
+            information.message().starts_with("database is locked")
+                || information
+                    .message()
+                    .starts_with("database table is locked")
+        }
+        _ => false,
     }
 }
 
@@ -43,15 +288,24 @@ pub type QueryResult<T> = diesel::result::QueryResult<T>;
 pub use diesel::result::OptionalExtension;
 
 pub trait TransactionsRepository {
+    /// Creates a transaction, then applies the rules matching it.
     fn create_transaction(&self, new_transaction: &NewTransaction) -> QueryResult<Transaction>;
     fn list_transactions(&self) -> QueryResult<Vec<Transaction>>;
+    fn get_transaction_by_id(&self, transaction_id: &TransactionId) -> QueryResult<Transaction>;
+    fn get_transaction_by_external_id(&self, external_id: &str) -> QueryResult<Transaction>;
     fn get_transactions_for_account(&self, account_id: &AccountId)
         -> QueryResult<Vec<Transaction>>;
+    fn get_transactions_for_payee(&self, payee_id: &PayeeId) -> QueryResult<Vec<Transaction>>;
     fn get_transactions_for_account_before_date_included(
         &self,
         account_id: &AccountId,
         date: &chrono::NaiveDate,
     ) -> QueryResult<Vec<Transaction>>;
+    fn get_transactions_between_dates(
+        &self,
+        from: &chrono::NaiveDate,
+        to: &chrono::NaiveDate,
+    ) -> QueryResult<Vec<Transaction>>;
 }
 
 pub trait AccountsRepository {
@@ -61,23 +315,155 @@ pub trait AccountsRepository {
     fn get_account_by_id(&self, account_id: &AccountId) -> QueryResult<Account>;
     fn get_account_by_name(&self, account_name: &str) -> QueryResult<Account>;
     fn update_account_initial_balance(&self, account: &Account) -> QueryResult<Account>;
+    /// Balance as of today, leaving out the transactions dated in the future.
     fn get_account_balance(&self, account_id: &AccountId) -> QueryResult<i64>;
     fn get_account_balance_as_of_date(
         &self,
         account_id: &AccountId,
         date: &chrono::NaiveDate,
     ) -> QueryResult<i64>;
+    /// Balance once all the transactions, including those dated in the
+    /// future, are made.
+    fn get_account_projected_balance(&self, account_id: &AccountId) -> QueryResult<i64>;
+    /// Balances at the end of each day from `from` to `to`, both included.
+    fn get_account_balance_history(
+        &self,
+        account_id: &AccountId,
+        from: &chrono::NaiveDate,
+        to: &chrono::NaiveDate,
+    ) -> QueryResult<Vec<DailyBalance>>;
+    /// Balances at the end of `from` and of each of the `days` following it,
+    /// counting the transactions dated after `from` and the occurrences of
+    /// recurring transactions.
+    ///
+    /// Recurring transactions are expected to be recorded up to `from`, so
+    /// their occurrences until then are left out.
+    fn forecast_account_balance(
+        &self,
+        account_id: &AccountId,
+        from: &chrono::NaiveDate,
+        days: u32,
+    ) -> QueryResult<Vec<DailyBalance>>;
+}
+
+pub trait CategoriesRepository {
+    fn create_category(&self, new_category: &NewCategory) -> QueryResult<Category>;
+    fn list_categories(&self) -> QueryResult<Vec<Category>>;
+    fn get_category_by_name(&self, category_name: &str) -> QueryResult<Category>;
+}
+
+pub trait BudgetsRepository {
+    fn create_budget(&self, new_budget: &NewBudget) -> QueryResult<Budget>;
+    fn list_budgets(&self) -> QueryResult<Vec<Budget>>;
+    fn get_budget_by_name(&self, budget_name: &str) -> QueryResult<Budget>;
+}
+
+pub trait TagsRepository {
+    fn create_tag(&self, new_tag: &NewTag) -> QueryResult<Tag>;
+    fn list_tags(&self) -> QueryResult<Vec<Tag>>;
+    fn get_tag_by_name(&self, tag_name: &str) -> QueryResult<Tag>;
+    fn add_tag_to_transaction(
+        &self,
+        transaction_id: &TransactionId,
+        tag_id: &TagId,
+    ) -> QueryResult<()>;
+    fn get_tags_for_transaction(&self, transaction_id: &TransactionId) -> QueryResult<Vec<Tag>>;
+}
+
+pub trait AttachmentsRepository {
+    fn create_attachment(&self, new_attachment: &NewAttachment) -> QueryResult<Attachment>;
+    fn get_attachments_for_transaction(
+        &self,
+        transaction_id: &TransactionId,
+    ) -> QueryResult<Vec<Attachment>>;
+}
+
+pub trait PayeesRepository {
+    fn create_payee(&self, new_payee: &NewPayee) -> QueryResult<Payee>;
+    fn list_payees(&self) -> QueryResult<Vec<Payee>>;
+    fn get_payee_by_id(&self, payee_id: &PayeeId) -> QueryResult<Payee>;
+    fn get_payee_by_name(&self, payee_name: &str) -> QueryResult<Payee>;
+    fn update_payee_defaults(&self, payee: &Payee) -> QueryResult<Payee>;
+    fn add_payee_alias(&self, payee_id: &PayeeId, alias: &str) -> QueryResult<()>;
+    fn get_aliases_for_payee(&self, payee_id: &PayeeId) -> QueryResult<Vec<String>>;
+    /// Finds the payee of a counterparty name from the bank, by its
+    /// normalized name or one of its aliases, and creates it when there is
+    /// none.
+    fn get_or_create_payee(&self, counterparty: &str) -> QueryResult<Payee>;
+}
+
+pub trait RecurringTransactionsRepository {
+    fn create_recurring_transaction(
+        &self,
+        new_recurring_transaction: &NewRecurringTransaction,
+    ) -> QueryResult<RecurringTransaction>;
+    fn list_recurring_transactions(&self) -> QueryResult<Vec<RecurringTransaction>>;
+    fn get_recurring_transactions_for_account(
+        &self,
+        account_id: &AccountId,
+    ) -> QueryResult<Vec<RecurringTransaction>>;
+}
+
+pub trait ReportsRepository {
+    /// Income and expenses of each month from `from` to `to`, both included.
+    fn get_income_expense_report(
+        &self,
+        from: &Month,
+        to: &Month,
+    ) -> QueryResult<Vec<IncomeExpense>>;
+    /// Net worth at the end of each period from `from` to `to`.
+    fn get_net_worth_report(
+        &self,
+        from: &chrono::NaiveDate,
+        to: &chrono::NaiveDate,
+        period: Period,
+    ) -> QueryResult<Vec<NetWorth>>;
+    /// Spending of `month` grouped by `group`, compared with the month
+    /// before.
+    fn get_spending_report(
+        &self,
+        month: &Month,
+        group: SpendingGroup,
+    ) -> QueryResult<SpendingReport>;
+    /// Spending of each day of `month`.
+    fn get_daily_spending_report(&self, month: &Month) -> QueryResult<Vec<DailySpending>>;
+}
+
+pub trait IntegrityRepository {
+    /// Problems which the constraints of the schema do not prevent, or which
+    /// were written before they were enforced.
+    fn check_integrity(&self) -> QueryResult<Vec<Problem>>;
+    /// Fixes `problem` if it is fixable, returning whether it was fixed.
+    ///
+    /// A negative transaction is not fixed while one of its accounts does not
+    /// exist, as its accounts cannot be swapped before that dangling
+    /// reference is fixed by hand.
+    fn fix_problem(&self, problem: &Problem) -> QueryResult<bool>;
+}
+
+pub trait RulesRepository {
+    fn create_rule(&self, new_rule: &NewRule) -> Result<Rule, RuleError>;
+    fn list_rules(&self) -> QueryResult<Vec<Rule>>;
+    fn get_rule_by_name(&self, rule_name: &str) -> QueryResult<Rule>;
+    fn add_tag_to_rule(&self, rule_id: &RuleId, tag_id: &TagId) -> QueryResult<()>;
+    fn get_tags_for_rule(&self, rule_id: &RuleId) -> QueryResult<Vec<Tag>>;
+    fn get_matching_rules(&self, transaction: &Transaction) -> QueryResult<Vec<Rule>>;
+    /// Applies the rules matching a transaction in the order they were
+    /// created, so that later rules override earlier ones, and returns them.
+    fn apply_rules(&self, transaction_id: &TransactionId) -> QueryResult<Vec<Rule>>;
 }
 
 impl AccountsRepository for Client {
     fn create_account(&self, new_account: &models::accounts::NewAccount) -> QueryResult<Account> {
-        insert_into(schema::accounts::table)
-            .values(new_account)
-            .execute(&self.conn)?;
+        self.transaction(|| {
+            insert_into(schema::accounts::table)
+                .values(new_account)
+                .execute(&self.conn)?;
 
-        schema::accounts::table
-            .order(schema::accounts::id.desc())
-            .first::<Account>(&self.conn)
+            schema::accounts::table
+                .order(schema::accounts::id.desc())
+                .first::<Account>(&self.conn)
+        })
     }
 
     fn list_accounts(&self) -> QueryResult<Vec<Account>> {
@@ -111,13 +497,7 @@ impl AccountsRepository for Client {
     }
 
     fn get_account_balance(&self, account_id: &AccountId) -> QueryResult<i64> {
-        let initial_balance = self.get_account_by_id(account_id)?.initial_balance;
-        let transactions_sum = get_account_balance_from_transactions(
-            self.get_transactions_for_account(account_id)?,
-            account_id,
-        );
-
-        Ok(initial_balance + transactions_sum)
+        self.get_account_balance_as_of_date(account_id, &chrono::Local::now().naive_local().date())
     }
 
     fn get_account_balance_as_of_date(
@@ -133,6 +513,92 @@ impl AccountsRepository for Client {
 
         Ok(initial_balance + transactions_sum)
     }
+
+    fn get_account_projected_balance(&self, account_id: &AccountId) -> QueryResult<i64> {
+        let initial_balance = self.get_account_by_id(account_id)?.initial_balance;
+        let transactions_sum = get_account_balance_from_transactions(
+            self.get_transactions_for_account(account_id)?,
+            account_id,
+        );
+
+        Ok(initial_balance + transactions_sum)
+    }
+
+    fn get_account_balance_history(
+        &self,
+        account_id: &AccountId,
+        from: &chrono::NaiveDate,
+        to: &chrono::NaiveDate,
+    ) -> QueryResult<Vec<DailyBalance>> {
+        let mut balance = self.get_account_by_id(account_id)?.initial_balance;
+        let mut transactions = self
+            .get_transactions_for_account_before_date_included(account_id, to)?
+            .into_iter()
+            .rev()
+            .peekable();
+
+        Ok(Period::Day
+            .ends_between(from, to)
+            .into_iter()
+            .map(|date| {
+                while let Some(transaction) =
+                    transactions.next_if(|transaction| transaction.date <= date)
+                {
+                    if transaction.destination_account_id == *account_id {
+                        balance += transaction.amount;
+                    }
+                    if transaction.source_account_id == *account_id {
+                        balance -= transaction.amount;
+                    }
+                }
+
+                DailyBalance { date, balance }
+            })
+            .collect())
+    }
+
+    fn forecast_account_balance(
+        &self,
+        account_id: &AccountId,
+        from: &chrono::NaiveDate,
+        days: u32,
+    ) -> QueryResult<Vec<DailyBalance>> {
+        let first_day = *from + Duration::days(1);
+        let last_day = *from + Duration::days(days.into());
+        let signed_amount = |source_account_id: AccountId, amount: i64| {
+            if source_account_id == *account_id {
+                -amount
+            } else {
+                amount
+            }
+        };
+
+        let mut changes = HashMap::new();
+        for transaction in self.get_transactions_for_account(account_id)? {
+            if transaction.date >= first_day && transaction.date <= last_day {
+                *changes.entry(transaction.date).or_insert(0) +=
+                    signed_amount(transaction.source_account_id, transaction.amount);
+            }
+        }
+        for recurring_transaction in self.get_recurring_transactions_for_account(account_id)? {
+            for date in recurring_transaction.occurrences_between(&first_day, &last_day) {
+                *changes.entry(date).or_insert(0) += signed_amount(
+                    recurring_transaction.source_account_id,
+                    recurring_transaction.amount,
+                );
+            }
+        }
+
+        let mut balance = self.get_account_balance_as_of_date(account_id, from)?;
+        Ok((0..=days)
+            .map(|day| {
+                let date = *from + Duration::days(day.into());
+                balance += changes.get(&date).unwrap_or(&0);
+
+                DailyBalance { date, balance }
+            })
+            .collect())
+    }
 }
 
 fn get_transactions_amount_sum(transactions: Vec<Transaction>) -> i64 {
@@ -156,26 +622,80 @@ fn get_account_balance_from_transactions(
     credit_sum - debit_sum
 }
 
+/// Most recent first. Within a day, transactions without a time of day come
+/// after the others, then the most recently recorded come first, so that
+/// the order is the same on every listing.
+fn transactions_order() -> (
+    diesel::helper_types::Desc<schema::transactions::date>,
+    diesel::helper_types::Desc<schema::transactions::time>,
+    diesel::helper_types::Desc<schema::transactions::id>,
+) {
+    (
+        schema::transactions::date.desc(),
+        schema::transactions::time.desc(),
+        schema::transactions::id.desc(),
+    )
+}
+
+/// The name of a category or payee in a spending report, which does not
+/// exist for a dangling reference.
+fn spending_name(name: Option<&String>) -> String {
+    name.cloned()
+        .unwrap_or_else(|| reports::UNKNOWN_SPENDING_NAME.to_string())
+}
+
 impl TransactionsRepository for Client {
     fn create_transaction(
         &self,
         new_transaction: &models::transactions::NewTransaction,
     ) -> QueryResult<Transaction> {
-        insert_into(schema::transactions::table)
-            .values(new_transaction)
-            .execute(&self.conn)?;
+        self.transaction(|| {
+            let default_category_id = match (new_transaction.category_id, new_transaction.payee_id)
+            {
+                (None, Some(payee_id)) => self.get_payee_by_id(&payee_id)?.default_category_id,
+                _ => None,
+            };
+            insert_into(schema::transactions::table)
+                .values((
+                    &NewTransaction {
+                        category_id: new_transaction.category_id.or(default_category_id),
+                        ..*new_transaction
+                    },
+                    schema::transactions::created_at.eq(now),
+                    schema::transactions::updated_at.eq(now),
+                ))
+                .execute(&self.conn)?;
 
-        schema::transactions::table
-            .order(schema::transactions::id.desc())
-            .first::<Transaction>(&self.conn)
+            let transaction = schema::transactions::table
+                .order(schema::transactions::id.desc())
+                .first::<Transaction>(&self.conn)?;
+
+            if self.apply_compiled_rules(&transaction)?.is_empty() {
+                Ok(transaction)
+            } else {
+                self.get_transaction_by_id(&transaction.id)
+            }
+        })
     }
 
     fn list_transactions(&self) -> QueryResult<Vec<Transaction>> {
         schema::transactions::table
-            .order(schema::transactions::date.desc())
+            .order(transactions_order())
             .get_results(&self.conn)
     }
 
+    fn get_transaction_by_id(&self, transaction_id: &TransactionId) -> QueryResult<Transaction> {
+        schema::transactions::table
+            .filter(schema::transactions::id.eq(transaction_id))
+            .first::<Transaction>(&self.conn)
+    }
+
+    fn get_transaction_by_external_id(&self, external_id: &str) -> QueryResult<Transaction> {
+        schema::transactions::table
+            .filter(schema::transactions::external_id.eq(external_id))
+            .first::<Transaction>(&self.conn)
+    }
+
     fn get_transactions_for_account(
         &self,
         account_id: &AccountId,
@@ -186,7 +706,14 @@ impl TransactionsRepository for Client {
                     .eq(account_id)
                     .or(schema::transactions::destination_account_id.eq(account_id)),
             )
-            .order(schema::transactions::date.desc())
+            .order(transactions_order())
+            .get_results(&self.conn)
+    }
+
+    fn get_transactions_for_payee(&self, payee_id: &PayeeId) -> QueryResult<Vec<Transaction>> {
+        schema::transactions::table
+            .filter(schema::transactions::payee_id.eq(payee_id))
+            .order(transactions_order())
             .get_results(&self.conn)
     }
 
@@ -202,7 +729,777 @@ impl TransactionsRepository for Client {
                     .or(schema::transactions::destination_account_id.eq(account_id))
                     .and(schema::transactions::date.le(date)),
             )
-            .order(schema::transactions::date.desc())
+            .order(transactions_order())
+            .get_results(&self.conn)
+    }
+
+    fn get_transactions_between_dates(
+        &self,
+        from: &chrono::NaiveDate,
+        to: &chrono::NaiveDate,
+    ) -> QueryResult<Vec<Transaction>> {
+        schema::transactions::table
+            .filter(schema::transactions::date.between(from, to))
+            .order(transactions_order())
+            .get_results(&self.conn)
+    }
+}
+
+impl CategoriesRepository for Client {
+    fn create_category(&self, new_category: &NewCategory) -> QueryResult<Category> {
+        self.transaction(|| {
+            insert_into(schema::categories::table)
+                .values(new_category)
+                .execute(&self.conn)?;
+
+            schema::categories::table
+                .order(schema::categories::id.desc())
+                .first::<Category>(&self.conn)
+        })
+    }
+
+    fn list_categories(&self) -> QueryResult<Vec<Category>> {
+        schema::categories::table
+            .order(schema::categories::name)
+            .get_results(&self.conn)
+    }
+
+    fn get_category_by_name(&self, category_name: &str) -> QueryResult<Category> {
+        schema::categories::table
+            .filter(schema::categories::name.eq(category_name))
+            .first::<Category>(&self.conn)
+    }
+}
+
+impl BudgetsRepository for Client {
+    fn create_budget(&self, new_budget: &NewBudget) -> QueryResult<Budget> {
+        self.transaction(|| {
+            insert_into(schema::budgets::table)
+                .values(new_budget)
+                .execute(&self.conn)?;
+
+            schema::budgets::table
+                .order(schema::budgets::id.desc())
+                .first::<Budget>(&self.conn)
+        })
+    }
+
+    fn list_budgets(&self) -> QueryResult<Vec<Budget>> {
+        schema::budgets::table
+            .order(schema::budgets::name)
+            .get_results(&self.conn)
+    }
+
+    fn get_budget_by_name(&self, budget_name: &str) -> QueryResult<Budget> {
+        schema::budgets::table
+            .filter(schema::budgets::name.eq(budget_name))
+            .first::<Budget>(&self.conn)
+    }
+}
+
+impl TagsRepository for Client {
+    fn create_tag(&self, new_tag: &NewTag) -> QueryResult<Tag> {
+        self.transaction(|| {
+            insert_into(schema::tags::table)
+                .values(new_tag)
+                .execute(&self.conn)?;
+
+            schema::tags::table
+                .order(schema::tags::id.desc())
+                .first::<Tag>(&self.conn)
+        })
+    }
+
+    fn list_tags(&self) -> QueryResult<Vec<Tag>> {
+        schema::tags::table
+            .order(schema::tags::name)
+            .get_results(&self.conn)
+    }
+
+    fn get_tag_by_name(&self, tag_name: &str) -> QueryResult<Tag> {
+        schema::tags::table
+            .filter(schema::tags::name.eq(tag_name))
+            .first::<Tag>(&self.conn)
+    }
+
+    fn add_tag_to_transaction(
+        &self,
+        transaction_id: &TransactionId,
+        tag_id: &TagId,
+    ) -> QueryResult<()> {
+        insert_or_ignore_into(schema::transaction_tags::table)
+            .values(&NewTransactionTag {
+                transaction_id: *transaction_id,
+                tag_id: *tag_id,
+            })
+            .execute(&self.conn)?;
+
+        Ok(())
+    }
+
+    fn get_tags_for_transaction(&self, transaction_id: &TransactionId) -> QueryResult<Vec<Tag>> {
+        schema::tags::table
+            .inner_join(schema::transaction_tags::table)
+            .filter(schema::transaction_tags::transaction_id.eq(transaction_id))
+            .order(schema::tags::name)
+            .select(schema::tags::all_columns)
+            .get_results(&self.conn)
+    }
+}
+
+impl AttachmentsRepository for Client {
+    fn create_attachment(&self, new_attachment: &NewAttachment) -> QueryResult<Attachment> {
+        self.transaction(|| {
+            insert_into(schema::attachments::table)
+                .values(new_attachment)
+                .execute(&self.conn)?;
+
+            schema::attachments::table
+                .order(schema::attachments::id.desc())
+                .first::<Attachment>(&self.conn)
+        })
+    }
+
+    fn get_attachments_for_transaction(
+        &self,
+        transaction_id: &TransactionId,
+    ) -> QueryResult<Vec<Attachment>> {
+        schema::attachments::table
+            .filter(schema::attachments::transaction_id.eq(transaction_id))
+            .order(schema::attachments::id)
+            .get_results(&self.conn)
+    }
+}
+
+impl PayeesRepository for Client {
+    fn create_payee(&self, new_payee: &NewPayee) -> QueryResult<Payee> {
+        self.transaction(|| {
+            insert_into(schema::payees::table)
+                .values(new_payee)
+                .execute(&self.conn)?;
+
+            schema::payees::table
+                .order(schema::payees::id.desc())
+                .first::<Payee>(&self.conn)
+        })
+    }
+
+    fn list_payees(&self) -> QueryResult<Vec<Payee>> {
+        schema::payees::table
+            .order(schema::payees::name)
+            .get_results(&self.conn)
+    }
+
+    fn get_payee_by_id(&self, payee_id: &PayeeId) -> QueryResult<Payee> {
+        schema::payees::table
+            .filter(schema::payees::id.eq(payee_id))
+            .first::<Payee>(&self.conn)
+    }
+
+    fn get_payee_by_name(&self, payee_name: &str) -> QueryResult<Payee> {
+        schema::payees::table
+            .filter(schema::payees::name.eq(payee_name))
+            .first::<Payee>(&self.conn)
+    }
+
+    fn update_payee_defaults(&self, payee: &Payee) -> QueryResult<Payee> {
+        update(schema::payees::table.filter(schema::payees::id.eq(payee.id)))
+            .set((
+                schema::payees::default_category_id.eq(payee.default_category_id),
+                schema::payees::default_account_id.eq(payee.default_account_id),
+            ))
+            .execute(&self.conn)?;
+
+        self.get_payee_by_id(&payee.id)
+    }
+
+    fn add_payee_alias(&self, payee_id: &PayeeId, alias: &str) -> QueryResult<()> {
+        replace_into(schema::payee_aliases::table)
+            .values(&NewPayeeAlias {
+                alias: &normalize_payee_name(alias),
+                payee_id: *payee_id,
+            })
+            .execute(&self.conn)?;
+
+        Ok(())
+    }
+
+    fn get_aliases_for_payee(&self, payee_id: &PayeeId) -> QueryResult<Vec<String>> {
+        schema::payee_aliases::table
+            .filter(schema::payee_aliases::payee_id.eq(payee_id))
+            .order(schema::payee_aliases::alias)
+            .select(schema::payee_aliases::alias)
+            .get_results(&self.conn)
+    }
+
+    fn get_or_create_payee(&self, counterparty: &str) -> QueryResult<Payee> {
+        let name = normalize_payee_name(counterparty);
+
+        let aliased = schema::payees::table
+            .inner_join(schema::payee_aliases::table)
+            .filter(schema::payee_aliases::alias.eq(&name))
+            .select(schema::payees::all_columns)
+            .first::<Payee>(&self.conn)
+            .optional()?;
+        if let Some(payee) = aliased {
+            return Ok(payee);
+        }
+
+        match self.get_payee_by_name(&name).optional()? {
+            Some(payee) => Ok(payee),
+            None => self.create_payee(&NewPayee {
+                name: &name,
+                default_category_id: None,
+                default_account_id: None,
+            }),
+        }
+    }
+}
+
+impl RecurringTransactionsRepository for Client {
+    fn create_recurring_transaction(
+        &self,
+        new_recurring_transaction: &NewRecurringTransaction,
+    ) -> QueryResult<RecurringTransaction> {
+        self.transaction(|| {
+            insert_into(schema::recurring_transactions::table)
+                .values(new_recurring_transaction)
+                .execute(&self.conn)?;
+
+            schema::recurring_transactions::table
+                .order(schema::recurring_transactions::id.desc())
+                .first::<RecurringTransaction>(&self.conn)
+        })
+    }
+
+    fn list_recurring_transactions(&self) -> QueryResult<Vec<RecurringTransaction>> {
+        schema::recurring_transactions::table
+            .order(schema::recurring_transactions::id)
+            .get_results(&self.conn)
+    }
+
+    fn get_recurring_transactions_for_account(
+        &self,
+        account_id: &AccountId,
+    ) -> QueryResult<Vec<RecurringTransaction>> {
+        schema::recurring_transactions::table
+            .filter(
+                schema::recurring_transactions::source_account_id
+                    .eq(account_id)
+                    .or(schema::recurring_transactions::destination_account_id.eq(account_id)),
+            )
+            .order(schema::recurring_transactions::id)
             .get_results(&self.conn)
     }
 }
+
+impl ReportsRepository for Client {
+    fn get_income_expense_report(
+        &self,
+        from: &Month,
+        to: &Month,
+    ) -> QueryResult<Vec<IncomeExpense>> {
+        let account_types = self
+            .list_accounts()?
+            .into_iter()
+            .map(|account| (account.id, account.account_type))
+            .collect::<HashMap<_, _>>();
+
+        let mut report = from
+            .until(*to)
+            .map(|month| IncomeExpense {
+                month,
+                income: 0,
+                expense: 0,
+            })
+            .collect::<Vec<_>>();
+        for transaction in self.get_transactions_between_dates(&from.first_day(), &to.last_day())? {
+            let month = Month::of(&transaction.date);
+            let income_expense = report
+                .iter_mut()
+                .find(|income_expense| income_expense.month == month)
+                .unwrap();
+
+            // Accounts which do not exist are neither Revenue nor Expense
+            // accounts: `rufm doctor` reports the transactions using them.
+            if account_types.get(&transaction.source_account_id) == Some(&AccountType::Revenue) {
+                income_expense.income += transaction.amount;
+            }
+            if account_types.get(&transaction.destination_account_id) == Some(&AccountType::Expense)
+            {
+                income_expense.expense += transaction.amount;
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn get_net_worth_report(
+        &self,
+        from: &chrono::NaiveDate,
+        to: &chrono::NaiveDate,
+        period: Period,
+    ) -> QueryResult<Vec<NetWorth>> {
+        let asset_accounts = self.list_asset_accounts()?;
+        let asset_account_ids = asset_accounts
+            .iter()
+            .map(|account| account.id)
+            .collect::<HashSet<_>>();
+
+        let mut net_worth = asset_accounts
+            .iter()
+            .map(|account| account.initial_balance)
+            .sum::<i64>();
+        let mut transactions = schema::transactions::table
+            .filter(schema::transactions::date.le(to))
+            .order(schema::transactions::date)
+            .get_results::<Transaction>(&self.conn)?
+            .into_iter()
+            .peekable();
+
+        Ok(period
+            .ends_between(from, to)
+            .into_iter()
+            .map(|end| {
+                while let Some(transaction) =
+                    transactions.next_if(|transaction| transaction.date <= end)
+                {
+                    if asset_account_ids.contains(&transaction.destination_account_id) {
+                        net_worth += transaction.amount;
+                    }
+                    if asset_account_ids.contains(&transaction.source_account_id) {
+                        net_worth -= transaction.amount;
+                    }
+                }
+
+                NetWorth {
+                    date: end,
+                    net_worth,
+                }
+            })
+            .collect())
+    }
+
+    fn get_spending_report(
+        &self,
+        month: &Month,
+        group: SpendingGroup,
+    ) -> QueryResult<SpendingReport> {
+        let accounts = self
+            .list_accounts()?
+            .into_iter()
+            .map(|account| (account.id, account))
+            .collect::<HashMap<_, _>>();
+        let categories = self
+            .list_categories()?
+            .into_iter()
+            .map(|category| (category.id, category.name))
+            .collect::<HashMap<_, _>>();
+        let payees = self
+            .list_payees()?
+            .into_iter()
+            .map(|payee| (payee.id, payee.name))
+            .collect::<HashMap<_, _>>();
+
+        let previous_month = month.previous();
+        let mut totals = HashMap::<Option<String>, (i64, i64)>::new();
+        for transaction in
+            self.get_transactions_between_dates(&previous_month.first_day(), &month.last_day())?
+        {
+            // Accounts which do not exist are not Expense accounts.
+            let destination_account = match accounts.get(&transaction.destination_account_id) {
+                Some(account) if account.account_type == AccountType::Expense => account,
+                _ => continue,
+            };
+
+            let name = match group {
+                SpendingGroup::ExpenseAccount => Some(destination_account.name.clone()),
+                SpendingGroup::Category => transaction
+                    .category_id
+                    .map(|category_id| spending_name(categories.get(&category_id))),
+                SpendingGroup::Payee => transaction
+                    .payee_id
+                    .map(|payee_id| spending_name(payees.get(&payee_id))),
+            };
+            let (total, previous_total) = totals.entry(name).or_insert((0, 0));
+            if Month::of(&transaction.date) == *month {
+                *total += transaction.amount;
+            } else {
+                *previous_total += transaction.amount;
+            }
+        }
+
+        let total = totals.values().map(|(total, _)| total).sum::<i64>();
+        let previous_total = totals
+            .values()
+            .map(|(_, previous_total)| previous_total)
+            .sum::<i64>();
+        let mut spendings = totals
+            .into_iter()
+            .map(|(name, (group_total, group_previous_total))| Spending {
+                name,
+                total: group_total,
+                previous_total: group_previous_total,
+                share: if total == 0 {
+                    0.0
+                } else {
+                    group_total as f64 / total as f64
+                },
+            })
+            .collect::<Vec<_>>();
+        spendings.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+
+        Ok(SpendingReport {
+            month: *month,
+            total,
+            previous_total,
+            spendings,
+        })
+    }
+
+    fn get_daily_spending_report(&self, month: &Month) -> QueryResult<Vec<DailySpending>> {
+        let expense_account_ids = self
+            .list_accounts()?
+            .into_iter()
+            .filter(|account| account.account_type == AccountType::Expense)
+            .map(|account| account.id)
+            .collect::<HashSet<_>>();
+
+        let mut report = Period::Day
+            .ends_between(&month.first_day(), &month.last_day())
+            .into_iter()
+            .map(|date| DailySpending { date, total: 0 })
+            .collect::<Vec<_>>();
+        for transaction in
+            self.get_transactions_between_dates(&month.first_day(), &month.last_day())?
+        {
+            if expense_account_ids.contains(&transaction.destination_account_id) {
+                report[transaction.date.day0() as usize].total += transaction.amount;
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+impl IntegrityRepository for Client {
+    fn check_integrity(&self) -> QueryResult<Vec<Problem>> {
+        let mut problems = Vec::new();
+
+        for (table, column, _) in integrity::ACCOUNT_REFERENCES {
+            let references = sql_query(format!(
+                "SELECT id AS row_id, {column} AS account_id FROM {table} WHERE {column} IS NOT \
+                 NULL AND {column} NOT IN (SELECT id FROM accounts) ORDER BY id",
+                table = table,
+                column = column,
+            ))
+            .load::<AccountReference>(&self.conn)?;
+
+            problems.extend(references.into_iter().map(|reference| {
+                Problem::DanglingAccountReference {
+                    table,
+                    column,
+                    row_id: reference.row_id,
+                    account_id: AccountId(reference.account_id),
+                }
+            }));
+        }
+
+        let transactions = schema::transactions::table
+            .select((
+                schema::transactions::id,
+                schema::transactions::source_account_id,
+                schema::transactions::destination_account_id,
+                schema::transactions::amount,
+            ))
+            .order(schema::transactions::id)
+            .load::<(TransactionId, AccountId, AccountId, i64)>(&self.conn)?;
+        for (transaction_id, source_account_id, destination_account_id, amount) in transactions {
+            if source_account_id == destination_account_id {
+                problems.push(Problem::SameSourceAndDestination { transaction_id });
+            }
+            if amount <= 0 {
+                problems.push(Problem::NonPositiveAmount {
+                    transaction_id,
+                    amount,
+                });
+            }
+        }
+
+        let accounts = schema::accounts::table
+            .select((
+                schema::accounts::id,
+                schema::accounts::name,
+                schema::accounts::account_type,
+            ))
+            .order(schema::accounts::id)
+            .load::<(AccountId, String, i32)>(&self.conn)?;
+        let mut account_ids_by_name = HashMap::<&str, Vec<AccountId>>::new();
+        for (account_id, name, account_type) in &accounts {
+            if AccountType::try_from(*account_type).is_err() {
+                problems.push(Problem::InvalidAccountType {
+                    account_id: *account_id,
+                    account_type: *account_type,
+                });
+            }
+            account_ids_by_name
+                .entry(name)
+                .or_default()
+                .push(*account_id);
+        }
+        let mut duplicates = account_ids_by_name
+            .into_iter()
+            .filter(|(_, account_ids)| account_ids.len() > 1)
+            .collect::<Vec<_>>();
+        // In the order of the accounts, as they were listed by ID.
+        duplicates.sort_by_key(|(_, account_ids)| account_ids[0].0);
+        problems.extend(duplicates.into_iter().map(|(name, account_ids)| {
+            Problem::DuplicateAccountName {
+                name: name.to_string(),
+                account_ids,
+            }
+        }));
+
+        Ok(problems)
+    }
+
+    fn fix_problem(&self, problem: &Problem) -> QueryResult<bool> {
+        if !problem.is_fixable() {
+            return Ok(false);
+        }
+
+        match problem {
+            Problem::DanglingAccountReference {
+                table,
+                column,
+                row_id,
+                ..
+            } => {
+                sql_query(format!(
+                    "UPDATE {table} SET {column} = NULL WHERE id = ?",
+                    table = table,
+                    column = column,
+                ))
+                .bind::<diesel::sql_types::Integer, _>(row_id)
+                .execute(&self.conn)?;
+            }
+            Problem::NonPositiveAmount { transaction_id, .. } => {
+                let transaction = self.get_transaction_by_id(transaction_id)?;
+                let mut account_ids = vec![
+                    transaction.source_account_id,
+                    transaction.destination_account_id,
+                ];
+                account_ids.dedup();
+                let existing_accounts = schema::accounts::table
+                    .filter(schema::accounts::id.eq_any(&account_ids))
+                    .count()
+                    .get_result::<i64>(&self.conn)?;
+                if existing_accounts < account_ids.len() as i64 {
+                    return Ok(false);
+                }
+
+                update(
+                    schema::transactions::table.filter(schema::transactions::id.eq(transaction_id)),
+                )
+                .set((
+                    schema::transactions::source_account_id.eq(transaction.destination_account_id),
+                    schema::transactions::destination_account_id.eq(transaction.source_account_id),
+                    schema::transactions::amount.eq(-transaction.amount),
+                    schema::transactions::updated_at.eq(now),
+                ))
+                .execute(&self.conn)?;
+            }
+            Problem::DuplicateAccountName { name, account_ids } => {
+                for account_id in account_ids.iter().skip(1) {
+                    update(schema::accounts::table.filter(schema::accounts::id.eq(account_id)))
+                        .set(schema::accounts::name.eq(format!("{} (#{})", name, account_id.0)))
+                        .execute(&self.conn)?;
+                }
+            }
+            Problem::SameSourceAndDestination { .. } | Problem::InvalidAccountType { .. } => {
+                unreachable!("not fixable")
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/// The side of a transaction which is not an asset account.
+enum Counterparty {
+    /// The expense account of a withdrawal.
+    Destination,
+    /// The revenue account of a deposit, or the expense account of a refund.
+    Source,
+}
+
+impl Client {
+    fn get_compiled_rules(&self) -> QueryResult<Arc<CompiledRules>> {
+        if let Some(compiled_rules) = &*self.compiled_rules.borrow() {
+            return Ok(Arc::clone(compiled_rules));
+        }
+
+        let rule_tags = schema::rule_tags::table.get_results::<(RuleId, TagId)>(&self.conn)?;
+        let compiled_rules = Arc::new(CompiledRules::new(self.list_rules()?, &rule_tags));
+        *self.compiled_rules.borrow_mut() = Some(Arc::clone(&compiled_rules));
+
+        Ok(compiled_rules)
+    }
+
+    fn forget_compiled_rules(&self) {
+        *self.compiled_rules.borrow_mut() = None;
+    }
+
+    /// Applies the matching rules to `transaction` in a single update, and
+    /// returns them.
+    fn apply_compiled_rules(&self, transaction: &Transaction) -> QueryResult<Vec<Rule>> {
+        let compiled_rules = self.get_compiled_rules()?;
+        let rules = compiled_rules.matching(transaction).collect::<Vec<_>>();
+        if rules.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let counterparty = if rules
+            .iter()
+            .any(|rule| rule.rule.set_destination_account_id.is_some())
+        {
+            self.get_counterparty(transaction)?
+        } else {
+            None
+        };
+        let mut name = None;
+        let mut source_account_id = None;
+        let mut destination_account_id = None;
+        let mut category_id = None;
+        let mut transaction_tags = Vec::new();
+        for CompiledRule { rule, tag_ids, .. } in &rules {
+            name = rule.set_name.as_deref().or(name);
+            match (&counterparty, rule.set_destination_account_id) {
+                (Some(Counterparty::Destination), Some(account_id))
+                    if account_id != transaction.source_account_id =>
+                {
+                    destination_account_id = Some(account_id)
+                }
+                (Some(Counterparty::Source), Some(account_id))
+                    if account_id != transaction.destination_account_id =>
+                {
+                    source_account_id = Some(account_id)
+                }
+                _ => {}
+            }
+            category_id = rule.set_category_id.or(category_id);
+            transaction_tags.extend(tag_ids.iter().map(|tag_id| NewTransactionTag {
+                transaction_id: transaction.id,
+                tag_id: *tag_id,
+            }));
+        }
+
+        if name.is_some()
+            || source_account_id.is_some()
+            || destination_account_id.is_some()
+            || category_id.is_some()
+        {
+            update(schema::transactions::table.filter(schema::transactions::id.eq(transaction.id)))
+                .set((
+                    schema::transactions::name.eq(name.unwrap_or(&transaction.name)),
+                    schema::transactions::source_account_id
+                        .eq(source_account_id.unwrap_or(transaction.source_account_id)),
+                    schema::transactions::destination_account_id
+                        .eq(destination_account_id.unwrap_or(transaction.destination_account_id)),
+                    schema::transactions::category_id.eq(category_id.or(transaction.category_id)),
+                    schema::transactions::updated_at.eq(now),
+                ))
+                .execute(&self.conn)?;
+        }
+        if !transaction_tags.is_empty() {
+            insert_or_ignore_into(schema::transaction_tags::table)
+                .values(&transaction_tags)
+                .execute(&self.conn)?;
+        }
+
+        Ok(rules.into_iter().map(|rule| rule.rule.clone()).collect())
+    }
+
+    /// Transfers between asset accounts have no counterparty.
+    fn get_counterparty(&self, transaction: &Transaction) -> QueryResult<Option<Counterparty>> {
+        let source_account = self.get_account_by_id(&transaction.source_account_id)?;
+        let destination_account = self.get_account_by_id(&transaction.destination_account_id)?;
+
+        Ok(
+            match (
+                source_account.account_type,
+                destination_account.account_type,
+            ) {
+                (_, AccountType::Expense | AccountType::Revenue) => Some(Counterparty::Destination),
+                (AccountType::Expense | AccountType::Revenue, _) => Some(Counterparty::Source),
+                _ => None,
+            },
+        )
+    }
+}
+
+impl RulesRepository for Client {
+    fn create_rule(&self, new_rule: &NewRule) -> Result<Rule, RuleError> {
+        if let Some(name_pattern) = new_rule.name_pattern {
+            regex::Regex::new(name_pattern)?;
+        }
+
+        self.forget_compiled_rules();
+        Ok(self.transaction(|| {
+            insert_into(schema::rules::table)
+                .values(new_rule)
+                .execute(&self.conn)?;
+
+            schema::rules::table
+                .order(schema::rules::id.desc())
+                .first::<Rule>(&self.conn)
+        })?)
+    }
+
+    fn list_rules(&self) -> QueryResult<Vec<Rule>> {
+        schema::rules::table
+            .order(schema::rules::id)
+            .get_results(&self.conn)
+    }
+
+    fn get_rule_by_name(&self, rule_name: &str) -> QueryResult<Rule> {
+        schema::rules::table
+            .filter(schema::rules::name.eq(rule_name))
+            .first::<Rule>(&self.conn)
+    }
+
+    fn add_tag_to_rule(&self, rule_id: &RuleId, tag_id: &TagId) -> QueryResult<()> {
+        self.forget_compiled_rules();
+        insert_or_ignore_into(schema::rule_tags::table)
+            .values(&NewRuleTag {
+                rule_id: *rule_id,
+                tag_id: *tag_id,
+            })
+            .execute(&self.conn)?;
+
+        Ok(())
+    }
+
+    fn get_tags_for_rule(&self, rule_id: &RuleId) -> QueryResult<Vec<Tag>> {
+        schema::tags::table
+            .inner_join(schema::rule_tags::table)
+            .filter(schema::rule_tags::rule_id.eq(rule_id))
+            .order(schema::tags::name)
+            .select(schema::tags::all_columns)
+            .get_results(&self.conn)
+    }
+
+    fn get_matching_rules(&self, transaction: &Transaction) -> QueryResult<Vec<Rule>> {
+        Ok(self
+            .get_compiled_rules()?
+            .matching(transaction)
+            .map(|rule| rule.rule.clone())
+            .collect())
+    }
+
+    fn apply_rules(&self, transaction_id: &TransactionId) -> QueryResult<Vec<Rule>> {
+        let transaction = self.get_transaction_by_id(transaction_id)?;
+
+        self.apply_compiled_rules(&transaction)
+    }
+}
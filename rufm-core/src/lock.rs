@@ -0,0 +1,36 @@
+use std::{fs::File, io};
+
+use fs2::FileExt;
+
+/// An advisory lock shared by the processes using a database, held until it
+/// is dropped.
+///
+/// It only excludes the other holders of a lock of the same name, not the
+/// other connections to the database.
+#[derive(Debug)]
+pub struct Lock {
+    file: Option<File>,
+}
+
+impl Lock {
+    /// Waits for the lock file at `path` to be free, then takes it.
+    pub(crate) fn acquire(path: &str) -> io::Result<Lock> {
+        let file = File::create(path)?;
+        file.lock_exclusive()?;
+
+        Ok(Lock { file: Some(file) })
+    }
+
+    /// A lock for a database which no other process can open.
+    pub(crate) fn in_memory() -> Lock {
+        Lock { file: None }
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        if let Some(file) = &self.file {
+            let _ = file.unlock();
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! The versions of the schema of a database, compared to the migrations
+//! embedded in this version of rufm.
+
+use diesel::{sql_query, sql_types::Text, RunQueryDsl, SqliteConnection};
+use thiserror::Error;
+
+use crate::QueryError;
+
+// `KNOWN_VERSIONS`, which the build script reads from `migrations/`.
+include!(concat!(env!("OUT_DIR"), "/known_versions.rs"));
+
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    #[error("Cannot run migrations: {0}")]
+    RunMigrationsError(#[from] diesel_migrations::RunMigrationsError),
+    #[error("Cannot read the migrations of the database: {0}")]
+    QueryError(#[from] QueryError),
+    #[error(
+        "The database was migrated by a newer version of rufm, which applied unknown \
+         migrations: {}",
+        .0.join(", ")
+    )]
+    NewerSchema(Vec<String>),
+    #[error(
+        "The database is opened read-only, but needs to be migrated first: {}",
+        .0.join(", ")
+    )]
+    PendingMigrations(Vec<String>),
+}
+
+/// The migrations of a database, by version.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MigrationStatus {
+    /// The known migrations applied to the database.
+    pub applied: Vec<String>,
+    /// The known migrations left to apply, oldest first.
+    pub pending: Vec<String>,
+    /// The migrations applied to the database by a newer version of rufm.
+    pub unknown: Vec<String>,
+}
+
+impl MigrationStatus {
+    pub fn is_up_to_date(&self) -> bool {
+        self.pending.is_empty() && self.unknown.is_empty()
+    }
+
+    /// Whether no migration was ever applied, as in a new database.
+    pub fn is_empty(&self) -> bool {
+        self.applied.is_empty() && self.unknown.is_empty()
+    }
+}
+
+/// The migrations applied by `Client::migrate`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct MigrationReport {
+    pub migrations: Vec<String>,
+    /// Path of the copy of the database taken before migrating it, if it was
+    /// not new.
+    pub backup_path: Option<String>,
+}
+
+#[derive(QueryableByName, Debug)]
+struct Version {
+    #[sql_type = "Text"]
+    version: String,
+}
+
+pub(crate) fn migration_status(conn: &SqliteConnection) -> Result<MigrationStatus, MigrationError> {
+    let known = known_versions();
+    let (applied, unknown) = applied_versions(conn)?
+        .into_iter()
+        .partition::<Vec<_>, _>(|version| known.contains(version));
+    let pending = known
+        .into_iter()
+        .filter(|version| !applied.contains(version))
+        .collect();
+
+    Ok(MigrationStatus {
+        applied,
+        pending,
+        unknown,
+    })
+}
+
+fn known_versions() -> Vec<String> {
+    KNOWN_VERSIONS
+        .iter()
+        .map(|version| version.to_string())
+        .collect()
+}
+
+fn applied_versions(conn: &SqliteConnection) -> Result<Vec<String>, MigrationError> {
+    let has_migrations_table = !sql_query(
+        "SELECT name AS version FROM sqlite_master WHERE type = 'table' AND name = \
+         '__diesel_schema_migrations'",
+    )
+    .load::<Version>(conn)?
+    .is_empty();
+    if !has_migrations_table {
+        return Ok(Vec::new());
+    }
+
+    Ok(
+        sql_query("SELECT version FROM __diesel_schema_migrations ORDER BY version")
+            .load::<Version>(conn)?
+            .into_iter()
+            .map(|version| version.version)
+            .collect(),
+    )
+}
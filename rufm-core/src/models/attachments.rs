@@ -0,0 +1,39 @@
+use crate::{models::transactions::TransactionId, schema::attachments};
+
+#[derive(DieselNewType, Debug, Hash, PartialEq, Eq, Copy, Clone)]
+pub struct AttachmentId(pub i32);
+
+/// A file kept with a transaction, such as a receipt.
+#[derive(Queryable, Debug, PartialEq, Eq)]
+pub struct Attachment {
+    pub id: AttachmentId,
+    pub transaction_id: TransactionId,
+    pub file_name: String,
+    /// MIME type of the content, e.g. `application/pdf`.
+    pub content_type: String,
+    pub content: Vec<u8>,
+}
+
+#[derive(Insertable, Debug)]
+#[table_name = "attachments"]
+pub struct NewAttachment<'a> {
+    pub transaction_id: TransactionId,
+    pub file_name: &'a str,
+    pub content_type: &'a str,
+    pub content: &'a [u8],
+}
+
+/// Guesses the MIME type of a file from its extension.
+pub fn content_type_from_file_name(file_name: &str) -> &'static str {
+    let extension = file_name
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_lowercase());
+
+    match extension.as_deref() {
+        Some("pdf") => "application/pdf",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("txt") => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,16 @@
+use crate::schema::budgets;
+
+#[derive(DieselNewType, Debug, Hash, PartialEq, Eq, Copy, Clone)]
+pub struct BudgetId(pub i32);
+
+#[derive(Queryable, Debug, PartialEq, Eq)]
+pub struct Budget {
+    pub id: BudgetId,
+    pub name: String,
+}
+
+#[derive(Insertable, Debug)]
+#[table_name = "budgets"]
+pub struct NewBudget<'a> {
+    pub name: &'a str,
+}
@@ -0,0 +1,16 @@
+use crate::schema::categories;
+
+#[derive(DieselNewType, Debug, Hash, PartialEq, Eq, Copy, Clone)]
+pub struct CategoryId(pub i32);
+
+#[derive(Queryable, Debug, PartialEq, Eq)]
+pub struct Category {
+    pub id: CategoryId,
+    pub name: String,
+}
+
+#[derive(Insertable, Debug)]
+#[table_name = "categories"]
+pub struct NewCategory<'a> {
+    pub name: &'a str,
+}
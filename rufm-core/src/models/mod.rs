@@ -1,2 +1,9 @@
 pub mod accounts;
+pub mod attachments;
+pub mod budgets;
+pub mod categories;
+pub mod payees;
+pub mod recurring_transactions;
+pub mod rules;
+pub mod tags;
 pub mod transactions;
@@ -0,0 +1,65 @@
+use crate::{
+    models::{accounts::AccountId, categories::CategoryId},
+    schema::{payee_aliases, payees},
+};
+
+#[derive(DieselNewType, Debug, Hash, PartialEq, Eq, Copy, Clone)]
+pub struct PayeeId(pub i32);
+
+/// Who a transaction was paid to or received from, whatever account it was
+/// booked on.
+#[derive(Queryable, Debug, PartialEq, Eq)]
+pub struct Payee {
+    pub id: PayeeId,
+    pub name: String,
+    /// Category of the payee's transactions which have none.
+    pub default_category_id: Option<CategoryId>,
+    /// Account importers book the payee's transactions on, instead of an
+    /// account named after the payee.
+    pub default_account_id: Option<AccountId>,
+}
+
+#[derive(Insertable, Debug)]
+#[table_name = "payees"]
+pub struct NewPayee<'a> {
+    pub name: &'a str,
+    pub default_category_id: Option<CategoryId>,
+    pub default_account_id: Option<AccountId>,
+}
+
+#[derive(Insertable, Debug)]
+#[table_name = "payee_aliases"]
+pub struct NewPayeeAlias<'a> {
+    pub alias: &'a str,
+    pub payee_id: PayeeId,
+}
+
+/// Words banks put before the payee's name.
+const PREFIXES: &[&str] = &[
+    "CARD", "CB", "POS", "DEBIT", "PURCHASE", "PAIEMENT", "PRLV", "VIR",
+];
+
+/// Strips what banks add to a payee's name, such as card numbers, dates and
+/// reference codes, so that `"CARD 1234 AMAZON EU 12/08 REF8X2K9Q"` becomes
+/// `"AMAZON EU"`.
+///
+/// Words without letters, or with three digits or more, are removed, as are
+/// the usual prefixes of card payments and transfers.
+pub fn normalize_payee_name(name: &str) -> String {
+    let words = name
+        .split_whitespace()
+        .filter(|word| {
+            let has_letters = word.chars().any(char::is_alphabetic);
+            let digits = word.chars().filter(char::is_ascii_digit).count();
+
+            has_letters && digits < 3
+        })
+        .skip_while(|word| PREFIXES.contains(&word.to_uppercase().as_str()))
+        .collect::<Vec<_>>();
+
+    if words.is_empty() {
+        name.trim().to_string()
+    } else {
+        words.join(" ")
+    }
+}
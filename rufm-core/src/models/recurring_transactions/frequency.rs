@@ -0,0 +1,71 @@
+use std::{convert::TryFrom, str::FromStr};
+
+use diesel::{
+    backend::Backend,
+    deserialize::{self, FromSql},
+    serialize::{self, ToSql},
+    sql_types::Integer,
+};
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy, AsExpression, FromSqlRow)]
+#[sql_type = "Integer"]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl<DB> FromSql<Integer, DB> for Frequency
+where
+    DB: Backend,
+    i32: FromSql<Integer, DB>,
+{
+    fn from_sql(bytes: Option<&DB::RawValue>) -> deserialize::Result<Self> {
+        Frequency::try_from(i32::from_sql(bytes)?).map_err(|e| e.into())
+    }
+}
+
+impl<DB> ToSql<Integer, DB> for Frequency
+where
+    DB: Backend,
+    i32: ToSql<Integer, DB>,
+{
+    fn to_sql<W: std::io::Write>(&self, out: &mut serialize::Output<W, DB>) -> serialize::Result {
+        i32::to_sql(&(*self).into(), out)
+    }
+}
+
+impl TryFrom<i32> for Frequency {
+    type Error = &'static str;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Frequency::Daily),
+            1 => Ok(Frequency::Weekly),
+            2 => Ok(Frequency::Monthly),
+            3 => Ok(Frequency::Yearly),
+            _ => Err("Conversion failed"),
+        }
+    }
+}
+
+impl From<Frequency> for i32 {
+    fn from(value: Frequency) -> Self {
+        value as Self
+    }
+}
+
+impl FromStr for Frequency {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "daily" | "day" => Ok(Frequency::Daily),
+            "weekly" | "week" => Ok(Frequency::Weekly),
+            "monthly" | "month" => Ok(Frequency::Monthly),
+            "yearly" | "year" => Ok(Frequency::Yearly),
+            _ => Err("expected daily, weekly, monthly or yearly"),
+        }
+    }
+}
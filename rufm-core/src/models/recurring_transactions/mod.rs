@@ -0,0 +1,5 @@
+mod frequency;
+mod recurring_transaction;
+
+pub use frequency::*;
+pub use recurring_transaction::*;
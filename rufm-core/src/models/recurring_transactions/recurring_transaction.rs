@@ -0,0 +1,73 @@
+use chrono::{Datelike, Duration, NaiveDate};
+
+use super::Frequency;
+use crate::{models::accounts::AccountId, schema::recurring_transactions};
+
+#[derive(DieselNewType, Debug, Hash, PartialEq, Eq, Copy, Clone)]
+pub struct RecurringTransactionId(pub i32);
+
+/// A transaction repeated at a fixed frequency, such as a salary or a rent,
+/// used to project balances.
+#[derive(Queryable, Debug, PartialEq, Eq)]
+pub struct RecurringTransaction {
+    pub id: RecurringTransactionId,
+    pub name: String,
+    pub source_account_id: AccountId,
+    pub destination_account_id: AccountId,
+    pub amount: i64,
+    pub frequency: Frequency,
+    /// Date of the first occurrence, which the next ones follow.
+    pub start_date: NaiveDate,
+    /// Last date an occurrence can fall on, if any.
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Insertable, Debug)]
+#[table_name = "recurring_transactions"]
+pub struct NewRecurringTransaction<'a> {
+    pub name: &'a str,
+    pub source_account_id: AccountId,
+    pub destination_account_id: AccountId,
+    pub amount: i64,
+    pub frequency: Frequency,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+}
+
+impl RecurringTransaction {
+    /// Dates of the occurrences between `from` and `to`, both included.
+    ///
+    /// Monthly and yearly occurrences fall on the day of month of the start
+    /// date, or on the last day of shorter months.
+    pub fn occurrences_between(&self, from: &NaiveDate, to: &NaiveDate) -> Vec<NaiveDate> {
+        let last = match self.end_date {
+            Some(end_date) if end_date < *to => end_date,
+            _ => *to,
+        };
+
+        (0..)
+            .map(|n| self.nth_occurrence(n))
+            .take_while(|date| *date <= last)
+            .filter(|date| date >= from)
+            .collect()
+    }
+
+    fn nth_occurrence(&self, n: i64) -> NaiveDate {
+        match self.frequency {
+            Frequency::Daily => self.start_date + Duration::days(n),
+            Frequency::Weekly => self.start_date + Duration::weeks(n),
+            Frequency::Monthly => add_months(&self.start_date, n),
+            Frequency::Yearly => add_months(&self.start_date, 12 * n),
+        }
+    }
+}
+
+fn add_months(date: &NaiveDate, months: i64) -> NaiveDate {
+    let month0 = i64::from(date.year()) * 12 + i64::from(date.month0()) + months;
+    let (year, month) = ((month0 / 12) as i32, (month0 % 12) as u32 + 1);
+
+    (date.day().min(28)..=date.day())
+        .rev()
+        .find_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+        .unwrap()
+}
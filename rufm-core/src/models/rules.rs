@@ -0,0 +1,108 @@
+use crate::{
+    models::{accounts::AccountId, categories::CategoryId, tags::TagId, transactions::Transaction},
+    schema::{rule_tags, rules},
+};
+
+#[derive(DieselNewType, Debug, Hash, PartialEq, Eq, Copy, Clone)]
+pub struct RuleId(pub i32);
+
+/// Changes applied to the transactions matching every condition of a rule.
+/// Conditions left empty match any transaction.
+#[derive(Queryable, Debug, PartialEq, Eq, Clone)]
+pub struct Rule {
+    pub id: RuleId,
+    pub name: String,
+    /// Regular expression the transaction name must match.
+    pub name_pattern: Option<String>,
+    pub min_amount: Option<i64>,
+    pub max_amount: Option<i64>,
+    pub source_account_id: Option<AccountId>,
+    pub set_name: Option<String>,
+    /// Account replacing the counterparty of the transaction: the
+    /// destination of a withdrawal, or the source of a deposit. Transfers
+    /// between asset accounts keep their accounts.
+    pub set_destination_account_id: Option<AccountId>,
+    pub set_category_id: Option<CategoryId>,
+}
+
+/// The rules with their name patterns compiled and their tags loaded, so
+/// that applying them to many transactions, e.g. during an import, takes no
+/// more queries.
+#[derive(Debug)]
+pub(crate) struct CompiledRules {
+    rules: Vec<CompiledRule>,
+}
+
+#[derive(Debug)]
+pub(crate) struct CompiledRule {
+    pub rule: Rule,
+    /// `None` without a name pattern. Patterns which do not compile, which
+    /// `create_rule` refuses, match no name.
+    name_regex: Option<Result<regex::Regex, regex::Error>>,
+    pub tag_ids: Vec<TagId>,
+}
+
+impl CompiledRules {
+    /// Compiles `rules`, in the order they are applied, with the tags of
+    /// every rule.
+    pub fn new(rules: Vec<Rule>, rule_tags: &[(RuleId, TagId)]) -> CompiledRules {
+        CompiledRules {
+            rules: rules
+                .into_iter()
+                .map(|rule| CompiledRule {
+                    name_regex: rule.name_pattern.as_deref().map(regex::Regex::new),
+                    tag_ids: rule_tags
+                        .iter()
+                        .filter(|(rule_id, _)| *rule_id == rule.id)
+                        .map(|(_, tag_id)| *tag_id)
+                        .collect(),
+                    rule,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn matching<'a>(
+        &'a self,
+        transaction: &'a Transaction,
+    ) -> impl Iterator<Item = &'a CompiledRule> {
+        self.rules
+            .iter()
+            .filter(move |rule| rule.matches(transaction))
+    }
+}
+
+impl CompiledRule {
+    fn matches(&self, transaction: &Transaction) -> bool {
+        let name_matches = match &self.name_regex {
+            Some(Ok(regex)) => regex.is_match(&transaction.name),
+            Some(Err(_)) => false,
+            None => true,
+        };
+
+        name_matches
+            && !matches!(self.rule.min_amount, Some(min) if transaction.amount < min)
+            && !matches!(self.rule.max_amount, Some(max) if transaction.amount > max)
+            && !matches!(self.rule.source_account_id, Some(id) if transaction.source_account_id != id)
+    }
+}
+
+#[derive(Insertable, Debug)]
+#[table_name = "rules"]
+pub struct NewRule<'a> {
+    pub name: &'a str,
+    pub name_pattern: Option<&'a str>,
+    pub min_amount: Option<i64>,
+    pub max_amount: Option<i64>,
+    pub source_account_id: Option<AccountId>,
+    pub set_name: Option<&'a str>,
+    pub set_destination_account_id: Option<AccountId>,
+    pub set_category_id: Option<CategoryId>,
+}
+
+#[derive(Insertable, Debug)]
+#[table_name = "rule_tags"]
+pub struct NewRuleTag {
+    pub rule_id: RuleId,
+    pub tag_id: TagId,
+}
@@ -0,0 +1,26 @@
+use crate::{
+    models::transactions::TransactionId,
+    schema::{tags, transaction_tags},
+};
+
+#[derive(DieselNewType, Debug, Hash, PartialEq, Eq, Copy, Clone)]
+pub struct TagId(pub i32);
+
+#[derive(Queryable, Debug, PartialEq, Eq)]
+pub struct Tag {
+    pub id: TagId,
+    pub name: String,
+}
+
+#[derive(Insertable, Debug)]
+#[table_name = "tags"]
+pub struct NewTag<'a> {
+    pub name: &'a str,
+}
+
+#[derive(Insertable, Debug)]
+#[table_name = "transaction_tags"]
+pub struct NewTransactionTag {
+    pub transaction_id: TransactionId,
+    pub tag_id: TagId,
+}
@@ -1,4 +1,7 @@
-use crate::{models::accounts::AccountId, schema::transactions};
+use crate::{
+    models::{accounts::AccountId, budgets::BudgetId, categories::CategoryId, payees::PayeeId},
+    schema::transactions,
+};
 
 #[derive(DieselNewType, Debug, Hash, PartialEq, Eq, Copy, Clone)]
 pub struct TransactionId(pub i32);
@@ -10,7 +13,35 @@ pub struct Transaction {
     pub source_account_id: AccountId,
     pub destination_account_id: AccountId,
     pub amount: i64,
+    /// Day of the transaction, in the timezone it happened in.
     pub date: chrono::NaiveDate,
+    pub external_id: Option<String>,
+    pub category_id: Option<CategoryId>,
+    pub budget_id: Option<BudgetId>,
+    pub notes: Option<String>,
+    pub payee_id: Option<PayeeId>,
+    /// Time of day, in the timezone it happened in, when it is known.
+    pub time: Option<chrono::NaiveTime>,
+    /// Offset from UTC of that timezone, in seconds.
+    pub utc_offset: Option<i32>,
+    /// When the transaction was recorded in rufm, in UTC.
+    pub created_at: chrono::NaiveDateTime,
+    /// When the transaction was last changed in rufm, in UTC.
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl Transaction {
+    /// The moment of the transaction, when both its time and timezone are
+    /// known.
+    pub fn date_time(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        use chrono::TimeZone;
+
+        let offset = chrono::FixedOffset::east_opt(self.utc_offset?)?;
+
+        offset
+            .from_local_datetime(&self.date.and_time(self.time?))
+            .single()
+    }
 }
 
 #[derive(Insertable, Debug)]
@@ -21,4 +52,11 @@ pub struct NewTransaction<'a> {
     pub destination_account_id: AccountId,
     pub amount: i64,
     pub date: chrono::NaiveDate,
+    pub external_id: Option<&'a str>,
+    pub category_id: Option<CategoryId>,
+    pub budget_id: Option<BudgetId>,
+    pub notes: Option<&'a str>,
+    pub payee_id: Option<PayeeId>,
+    pub time: Option<chrono::NaiveTime>,
+    pub utc_offset: Option<i32>,
 }
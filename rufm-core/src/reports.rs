@@ -0,0 +1,239 @@
+//! Typed reports over transactions, for frontends to display.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+/// A calendar month, written `2021-08`.
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub struct Month {
+    year: i32,
+    /// From 1 to 12.
+    month: u32,
+}
+
+impl Month {
+    /// The `month` (from 1 to 12) of `year`, if both are valid.
+    pub fn new(year: i32, month: u32) -> Option<Month> {
+        NaiveDate::from_ymd_opt(year, month, 1).map(|date| Month::of(&date))
+    }
+
+    pub fn of(date: &NaiveDate) -> Month {
+        Month {
+            year: date.year(),
+            month: date.month(),
+        }
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    pub fn first_day(&self) -> NaiveDate {
+        NaiveDate::from_ymd_opt(self.year, self.month, 1).unwrap()
+    }
+
+    pub fn last_day(&self) -> NaiveDate {
+        self.next().first_day() - Duration::days(1)
+    }
+
+    pub fn next(&self) -> Month {
+        match self.month {
+            12 => Month {
+                year: self.year + 1,
+                month: 1,
+            },
+            month => Month {
+                year: self.year,
+                month: month + 1,
+            },
+        }
+    }
+
+    pub fn previous(&self) -> Month {
+        match self.month {
+            1 => Month {
+                year: self.year - 1,
+                month: 12,
+            },
+            month => Month {
+                year: self.year,
+                month: month - 1,
+            },
+        }
+    }
+
+    /// This month and the following ones, up to `last` included.
+    pub fn until(self, last: Month) -> impl Iterator<Item = Month> {
+        std::iter::successors(Some(self), |month| Some(month.next()))
+            .take_while(move |month| *month <= last)
+    }
+}
+
+impl FromStr for Month {
+    type Err = chrono::ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NaiveDate::parse_from_str(&format!("{}-01", s), "%Y-%m-%d").map(|date| Month::of(&date))
+    }
+}
+
+impl fmt::Display for Month {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{:02}", self.year, self.month)
+    }
+}
+
+/// What came out of Revenue accounts and went into Expense accounts during a
+/// month.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct IncomeExpense {
+    pub month: Month,
+    pub income: i64,
+    pub expense: i64,
+}
+
+impl IncomeExpense {
+    pub fn net(&self) -> i64 {
+        self.income - self.expense
+    }
+
+    /// Share of the income which was not spent, `None` without income.
+    pub fn savings_rate(&self) -> Option<f64> {
+        if self.income == 0 {
+            None
+        } else {
+            Some(self.net() as f64 / self.income as f64)
+        }
+    }
+}
+
+/// Length of the periods a report is split into.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum Period {
+    Day,
+    /// From Monday to Sunday.
+    Week,
+    Month,
+    Year,
+}
+
+impl Period {
+    /// Last day of the period `date` belongs to.
+    pub fn end_of(&self, date: &NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => *date,
+            Period::Week => {
+                *date + Duration::days(6 - i64::from(date.weekday().num_days_from_monday()))
+            }
+            Period::Month => Month::of(date).last_day(),
+            Period::Year => NaiveDate::from_ymd_opt(date.year(), 12, 31).unwrap(),
+        }
+    }
+
+    /// Last days of the periods from `from` to `to`, the last one being `to`
+    /// itself when it ends a period early.
+    pub fn ends_between(&self, from: &NaiveDate, to: &NaiveDate) -> Vec<NaiveDate> {
+        let mut ends = Vec::new();
+        let mut end = self.end_of(from);
+        while end < *to {
+            ends.push(end);
+            end = self.end_of(&(end + Duration::days(1)));
+        }
+        if from <= to {
+            ends.push(*to);
+        }
+
+        ends
+    }
+}
+
+impl FromStr for Period {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" | "daily" => Ok(Period::Day),
+            "week" | "weekly" => Ok(Period::Week),
+            "month" | "monthly" => Ok(Period::Month),
+            "year" | "yearly" => Ok(Period::Year),
+            _ => Err("expected day, week, month or year"),
+        }
+    }
+}
+
+/// Combined balance of the Asset accounts at the end of a day.
+///
+/// Liabilities, such as credit cards and loans, are Asset accounts with a
+/// negative balance, and so are subtracted.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct NetWorth {
+    pub date: NaiveDate,
+    pub net_worth: i64,
+}
+
+/// What spending is grouped by.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum SpendingGroup {
+    ExpenseAccount,
+    Category,
+    Payee,
+}
+
+impl FromStr for SpendingGroup {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "account" => Ok(SpendingGroup::ExpenseAccount),
+            "category" => Ok(SpendingGroup::Category),
+            "payee" => Ok(SpendingGroup::Payee),
+            _ => Err("expected account, category or payee"),
+        }
+    }
+}
+
+/// What went into Expense accounts during a month, by group, the largest
+/// first.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SpendingReport {
+    pub month: Month,
+    pub total: i64,
+    pub previous_total: i64,
+    pub spendings: Vec<Spending>,
+}
+
+/// Name of the spending of the transactions whose category or payee does
+/// not exist.
+pub const UNKNOWN_SPENDING_NAME: &str = "(unknown)";
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Spending {
+    /// Name of the expense account, category or payee, `None` for the
+    /// transactions without a category or payee, and
+    /// `UNKNOWN_SPENDING_NAME` for those whose category or payee does not
+    /// exist.
+    pub name: Option<String>,
+    pub total: i64,
+    /// Total of the month before.
+    pub previous_total: i64,
+    /// Share of the month's spending, from 0 to 1.
+    pub share: f64,
+}
+
+impl Spending {
+    pub fn change(&self) -> i64 {
+        self.total - self.previous_total
+    }
+}
+
+/// What went into Expense accounts during a day.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DailySpending {
+    pub date: NaiveDate,
+    pub total: i64,
+}
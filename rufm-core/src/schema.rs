@@ -7,6 +7,94 @@ table! {
     }
 }
 
+table! {
+    attachments (id) {
+        id -> Integer,
+        transaction_id -> Integer,
+        file_name -> Text,
+        content_type -> Text,
+        content -> Binary,
+    }
+}
+
+table! {
+    budgets (id) {
+        id -> Integer,
+        name -> Text,
+    }
+}
+
+table! {
+    categories (id) {
+        id -> Integer,
+        name -> Text,
+    }
+}
+
+table! {
+    payee_aliases (alias) {
+        alias -> Text,
+        payee_id -> Integer,
+    }
+}
+
+table! {
+    payees (id) {
+        id -> Integer,
+        name -> Text,
+        default_category_id -> Nullable<Integer>,
+        default_account_id -> Nullable<Integer>,
+    }
+}
+
+table! {
+    recurring_transactions (id) {
+        id -> Integer,
+        name -> Text,
+        source_account_id -> Integer,
+        destination_account_id -> Integer,
+        amount -> BigInt,
+        frequency -> Integer,
+        start_date -> Date,
+        end_date -> Nullable<Date>,
+    }
+}
+
+table! {
+    rule_tags (rule_id, tag_id) {
+        rule_id -> Integer,
+        tag_id -> Integer,
+    }
+}
+
+table! {
+    rules (id) {
+        id -> Integer,
+        name -> Text,
+        name_pattern -> Nullable<Text>,
+        min_amount -> Nullable<BigInt>,
+        max_amount -> Nullable<BigInt>,
+        source_account_id -> Nullable<Integer>,
+        set_name -> Nullable<Text>,
+        set_destination_account_id -> Nullable<Integer>,
+        set_category_id -> Nullable<Integer>,
+    }
+}
+
+table! {
+    tags (id) {
+        id -> Integer,
+        name -> Text,
+    }
+}
+
+table! {
+    transaction_tags (transaction_id, tag_id) {
+        transaction_id -> Integer,
+        tag_id -> Integer,
+    }
+}
+
 table! {
     transactions (id) {
         id -> Integer,
@@ -15,7 +103,37 @@ table! {
         destination_account_id -> Integer,
         amount -> BigInt,
         date -> Date,
+        external_id -> Nullable<Text>,
+        category_id -> Nullable<Integer>,
+        budget_id -> Nullable<Integer>,
+        notes -> Nullable<Text>,
+        payee_id -> Nullable<Integer>,
+        time -> Nullable<Time>,
+        utc_offset -> Nullable<Integer>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
-allow_tables_to_appear_in_same_query!(accounts, transactions,);
+joinable!(attachments -> transactions (transaction_id));
+joinable!(payee_aliases -> payees (payee_id));
+joinable!(rule_tags -> rules (rule_id));
+joinable!(rule_tags -> tags (tag_id));
+joinable!(transaction_tags -> tags (tag_id));
+joinable!(transaction_tags -> transactions (transaction_id));
+joinable!(transactions -> payees (payee_id));
+
+allow_tables_to_appear_in_same_query!(
+    accounts,
+    attachments,
+    budgets,
+    categories,
+    payee_aliases,
+    payees,
+    recurring_transactions,
+    rule_tags,
+    rules,
+    tags,
+    transaction_tags,
+    transactions,
+);
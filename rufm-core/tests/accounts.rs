@@ -4,6 +4,9 @@ use rufm_core::{
     *,
 };
 
+mod common;
+use common::*;
+
 #[test]
 fn can_create_account() {
     let client = Client::new(None).unwrap();
@@ -78,6 +81,13 @@ fn spending_money_debits_the_given_amount() {
             destination_account_id: other_account.id,
             amount,
             date: get_first_day(),
+            external_id: None,
+            category_id: None,
+            budget_id: None,
+            notes: None,
+            payee_id: None,
+            time: None,
+            utc_offset: None,
         })
         .unwrap();
 
@@ -98,6 +108,13 @@ fn receiving_money_credits_the_given_amount() {
             destination_account_id: account.id,
             amount,
             date: get_first_day(),
+            external_id: None,
+            category_id: None,
+            budget_id: None,
+            notes: None,
+            payee_id: None,
+            time: None,
+            utc_offset: None,
         })
         .unwrap();
 
@@ -139,6 +156,49 @@ fn balance_after_transactions_is_valid() {
     assert_eq!(balance, -191);
 }
 
+#[test]
+fn balance_leaves_out_future_transactions() {
+    let (client, account, other_account) = setup_two_accounts().unwrap();
+    let tomorrow = chrono::Local::now().naive_local().date() + Duration::days(1);
+    client
+        .create_transaction(&new_transaction(
+            "rent",
+            account.id,
+            other_account.id,
+            800,
+            tomorrow,
+        ))
+        .unwrap();
+
+    assert_eq!(client.get_account_balance(&account.id).unwrap(), 0);
+    assert_eq!(
+        client.get_account_projected_balance(&account.id).unwrap(),
+        -800
+    );
+}
+
+#[test]
+fn balance_history_gives_the_balance_of_each_day() {
+    let (client, account, _, _) = setup_two_accounts_and_multiple_transactions().unwrap();
+
+    let history = client
+        .get_account_balance_history(
+            &account.id,
+            &(get_first_day() + Duration::days(2)),
+            &(get_first_day() + Duration::days(4)),
+        )
+        .unwrap();
+
+    assert_eq!(
+        history
+            .iter()
+            .map(|daily_balance| daily_balance.balance)
+            .collect::<Vec<_>>(),
+        vec![-60, -165, -191]
+    );
+    assert_eq!(history[0].date, get_first_day() + Duration::days(2));
+}
+
 // Helper functions
 
 fn get_first_day() -> NaiveDate {
@@ -188,6 +248,13 @@ fn setup_two_accounts_and_multiple_transactions(
             destination_account_id,
             amount,
             date: get_first_day() + Duration::days(days_offset),
+            external_id: None,
+            category_id: None,
+            budget_id: None,
+            notes: None,
+            payee_id: None,
+            time: None,
+            utc_offset: None,
         })
     })
     .collect::<Result<Vec<Transaction>, diesel::result::Error>>()?;
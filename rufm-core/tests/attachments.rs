@@ -0,0 +1,56 @@
+use rufm_core::{
+    models::{accounts::*, attachments::*, transactions::*},
+    *,
+};
+
+mod common;
+use common::*;
+
+#[test]
+fn can_attach_files_to_transaction() {
+    let client = Client::new(None).unwrap();
+    let transaction = create_laptop_purchase(&client);
+
+    let receipt = client
+        .create_attachment(&NewAttachment {
+            transaction_id: transaction.id,
+            file_name: "receipt.pdf",
+            content_type: "application/pdf",
+            content: b"%PDF-1.4",
+        })
+        .unwrap();
+
+    let attachments = client
+        .get_attachments_for_transaction(&transaction.id)
+        .unwrap();
+    assert_eq!(attachments, vec![receipt]);
+    assert_eq!(attachments[0].file_name, "receipt.pdf");
+    assert_eq!(attachments[0].content, b"%PDF-1.4");
+}
+
+#[test]
+fn content_type_is_guessed_from_file_name() {
+    assert_eq!(
+        content_type_from_file_name("receipt.PDF"),
+        "application/pdf"
+    );
+    assert_eq!(content_type_from_file_name("photo.jpeg"), "image/jpeg");
+    assert_eq!(
+        content_type_from_file_name("warranty"),
+        "application/octet-stream"
+    );
+}
+
+// Helper functions
+
+fn create_laptop_purchase(client: &Client) -> Transaction {
+    let checking = create_account(client, "Checking", AccountType::Asset);
+    let shop = create_account(client, "Shop", AccountType::Expense);
+
+    client
+        .create_transaction(&NewTransaction {
+            notes: Some("Two years warranty"),
+            ..new_transaction("Laptop", checking.id, shop.id, 99900, date(2021, 8, 29))
+        })
+        .unwrap()
+}
@@ -0,0 +1,108 @@
+use rufm_core::{models::accounts::*, *};
+
+mod common;
+use common::*;
+
+#[test]
+fn transaction_is_committed_on_success() {
+    let client = Client::new(None).unwrap();
+
+    client
+        .transaction::<_, QueryError, _>(|| client.create_account(&new_account("test")))
+        .unwrap();
+
+    assert_eq!(client.list_accounts().unwrap().len(), 1);
+}
+
+#[test]
+fn transaction_is_rolled_back_on_error() {
+    let client = Client::new(None).unwrap();
+
+    let result = client.transaction(|| {
+        client.create_account(&new_account("test"))?;
+
+        Err::<(), _>(QueryError::NotFound)
+    });
+
+    assert!(matches!(result, Err(QueryError::NotFound)));
+    assert!(client.list_accounts().unwrap().is_empty());
+}
+
+#[test]
+fn dry_run_is_always_rolled_back() {
+    let client = Client::new(None).unwrap();
+
+    let account = client
+        .dry_run::<_, QueryError, _>(|| {
+            client.create_account(&new_account("test"))?;
+
+            client.get_account_by_name("test")
+        })
+        .unwrap();
+
+    assert_eq!(account.name, "test");
+    assert!(client.list_accounts().unwrap().is_empty());
+}
+
+#[test]
+fn builder_can_refuse_to_create_a_missing_database() {
+    let database = TestDatabase::new("client-missing");
+
+    let result = ClientBuilder::new(database.path())
+        .create_if_missing(false)
+        .build();
+
+    assert!(matches!(
+        result,
+        Err(ClientCreationError::DatabaseNotFound(_))
+    ));
+    assert!(!std::path::Path::new(database.path()).exists());
+}
+
+#[test]
+fn read_only_client_refuses_writes() {
+    let database = TestDatabase::new("client-read_only");
+    create_account(&database.client(), "test", AccountType::Asset);
+
+    let client = ClientBuilder::new(database.path())
+        .read_only(true)
+        .build()
+        .unwrap();
+
+    assert_eq!(client.list_accounts().unwrap().len(), 1);
+    assert!(client.create_account(&new_account("other")).is_err());
+}
+
+#[test]
+fn builder_can_skip_migrations() {
+    let client = ClientBuilder::in_memory()
+        .run_migrations(false)
+        .build()
+        .unwrap();
+
+    assert!(client.list_accounts().is_err());
+}
+
+#[test]
+fn wal_client_writes_to_a_log() {
+    let database = TestDatabase::new("client-wal");
+
+    let client = ClientBuilder::new(database.path())
+        .wal(true)
+        .busy_timeout(std::time::Duration::from_secs(1))
+        .build()
+        .unwrap();
+    create_account(&client, "test", AccountType::Asset);
+
+    assert!(std::path::Path::new(&format!("{}-wal", database.path())).exists());
+}
+
+// Helper functions
+
+fn new_account(name: &str) -> NewAccount<'_> {
+    NewAccount {
+        name,
+        account_type: AccountType::Asset,
+        initial_balance: 0,
+    }
+}
@@ -0,0 +1,117 @@
+//! Fixtures shared by the integration tests, each of which uses only some of
+//! them.
+#![allow(dead_code)]
+
+use chrono::NaiveDate;
+use diesel::{Connection, RunQueryDsl, SqliteConnection};
+use rufm_core::{
+    models::{accounts::*, transactions::*},
+    *,
+};
+
+pub fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+pub fn create_account(client: &Client, name: &str, account_type: AccountType) -> Account {
+    client
+        .create_account(&NewAccount {
+            name,
+            account_type,
+            initial_balance: 0,
+        })
+        .unwrap()
+}
+
+/// A transaction without any of the optional fields, which tests set with
+/// `..new_transaction(...)`.
+pub fn new_transaction(
+    name: &str,
+    source_account_id: AccountId,
+    destination_account_id: AccountId,
+    amount: i64,
+    date: NaiveDate,
+) -> NewTransaction<'_> {
+    NewTransaction {
+        name,
+        source_account_id,
+        destination_account_id,
+        amount,
+        date,
+        external_id: None,
+        category_id: None,
+        budget_id: None,
+        notes: None,
+        payee_id: None,
+        time: None,
+        utc_offset: None,
+    }
+}
+
+pub fn create_transaction(
+    client: &Client,
+    name: &str,
+    source_account: &Account,
+    destination_account: &Account,
+    amount: i64,
+    date: NaiveDate,
+) -> Transaction {
+    client
+        .create_transaction(&new_transaction(
+            name,
+            source_account.id,
+            destination_account.id,
+            amount,
+            date,
+        ))
+        .unwrap()
+}
+
+/// A database file, removed with the files next to it when dropped.
+pub struct TestDatabase {
+    path: String,
+}
+
+impl TestDatabase {
+    pub fn new(name: &str) -> TestDatabase {
+        let path = std::env::temp_dir().join(format!("rufm-{}-{}.db", name, std::process::id()));
+        let database = TestDatabase {
+            path: path.to_str().unwrap().to_string(),
+        };
+        database.remove_files();
+
+        database
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn client(&self) -> Client {
+        Client::new(Some(&self.path)).unwrap()
+    }
+
+    /// Runs `statements` on a connection of its own, without the
+    /// constraints `Client` enforces.
+    pub fn execute_unchecked(&self, statements: &[&str]) {
+        let conn = SqliteConnection::establish(&self.path).unwrap();
+        diesel::sql_query("PRAGMA foreign_keys = OFF")
+            .execute(&conn)
+            .unwrap();
+        for statement in statements {
+            diesel::sql_query(*statement).execute(&conn).unwrap();
+        }
+    }
+
+    fn remove_files(&self) {
+        for suffix in &["", "-wal", "-shm", ".import.lock"] {
+            let _ = std::fs::remove_file(format!("{}{}", self.path, suffix));
+        }
+    }
+}
+
+impl Drop for TestDatabase {
+    fn drop(&mut self) {
+        self.remove_files();
+    }
+}
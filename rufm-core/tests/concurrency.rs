@@ -0,0 +1,143 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use diesel::result::DatabaseErrorKind;
+use rufm_core::{models::accounts::*, *};
+
+mod common;
+use common::*;
+
+const WRITERS: usize = 4;
+const TRANSACTIONS_PER_WRITER: usize = 25;
+
+#[test]
+fn several_writers_can_share_a_database() {
+    let database = TestDatabase::new("concurrency-writers");
+    let client = database.client();
+    let checking = create_account(&client, "Checking", AccountType::Asset).id;
+    let groceries = create_account(&client, "Groceries", AccountType::Asset).id;
+
+    let writers = (0..WRITERS)
+        .map(|writer| {
+            let path = database.path().to_string();
+            thread::spawn(move || {
+                let client = Client::new(Some(&path)).unwrap();
+                for i in 0..TRANSACTIONS_PER_WRITER {
+                    let name = format!("writer {} transaction {}", writer, i);
+                    let transaction = client
+                        .retry_if_busy(|| {
+                            client.create_transaction(&new_transaction(
+                                &name,
+                                checking,
+                                groceries,
+                                1,
+                                date(2021, 9, 1),
+                            ))
+                        })
+                        .unwrap();
+
+                    assert_eq!(transaction.name, name);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    assert_eq!(
+        client.list_transactions().unwrap().len(),
+        WRITERS * TRANSACTIONS_PER_WRITER
+    );
+    assert_eq!(
+        client.get_account_balance(&checking).unwrap(),
+        -((WRITERS * TRANSACTIONS_PER_WRITER) as i64)
+    );
+}
+
+#[test]
+fn locks_exclude_each_other() {
+    let database = TestDatabase::new("concurrency-locks");
+    database.client();
+    let holders = Arc::new(AtomicUsize::new(0));
+
+    let threads = (0..WRITERS)
+        .map(|_| {
+            let path = database.path().to_string();
+            let holders = Arc::clone(&holders);
+            thread::spawn(move || {
+                let client = Client::new(Some(&path)).unwrap();
+                for _ in 0..5 {
+                    let _lock = client.lock("import").unwrap();
+                    assert_eq!(holders.fetch_add(1, Ordering::SeqCst), 0);
+                    thread::sleep(Duration::from_millis(2));
+                    holders.fetch_sub(1, Ordering::SeqCst);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
+
+#[test]
+fn busy_errors_are_retried() {
+    let client = Client::new(None).unwrap();
+    let mut attempts = 0;
+
+    let result = client.retry_if_busy(|| {
+        attempts += 1;
+        if attempts < 3 {
+            Err(QueryError::DatabaseError(
+                DatabaseErrorKind::__Unknown,
+                Box::new("database is locked".to_string()),
+            ))
+        } else {
+            Ok(attempts)
+        }
+    });
+
+    assert_eq!(result.unwrap(), 3);
+}
+
+#[test]
+fn boxed_busy_errors_are_retried() {
+    let client = Client::new(None).unwrap();
+    let mut attempts = 0;
+
+    let result = client.retry_if_busy::<_, Box<dyn std::error::Error>, _>(|| {
+        attempts += 1;
+        if attempts < 2 {
+            Err(QueryError::DatabaseError(
+                DatabaseErrorKind::__Unknown,
+                Box::new("database is locked".to_string()),
+            )
+            .into())
+        } else {
+            Ok(attempts)
+        }
+    });
+
+    assert_eq!(result.unwrap(), 2);
+}
+
+#[test]
+fn other_errors_are_not_retried() {
+    let client = Client::new(None).unwrap();
+    let mut attempts = 0;
+
+    let result = client.retry_if_busy(|| {
+        attempts += 1;
+        Err::<(), _>(QueryError::NotFound)
+    });
+
+    assert!(matches!(result, Err(QueryError::NotFound)));
+    assert_eq!(attempts, 1);
+}
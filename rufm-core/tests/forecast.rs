@@ -0,0 +1,117 @@
+use chrono::NaiveDate;
+use rufm_core::{
+    forecast::*,
+    models::{accounts::*, recurring_transactions::*},
+    *,
+};
+
+mod common;
+use common::*;
+
+#[test]
+fn monthly_occurrences_fall_on_the_last_day_of_shorter_months() {
+    let rent = RecurringTransaction {
+        id: RecurringTransactionId(1),
+        name: "Rent".to_string(),
+        source_account_id: AccountId(1),
+        destination_account_id: AccountId(2),
+        amount: 800,
+        frequency: Frequency::Monthly,
+        start_date: date(2021, 1, 31),
+        end_date: Some(date(2021, 4, 30)),
+    };
+
+    assert_eq!(
+        rent.occurrences_between(&date(2021, 2, 1), &date(2021, 12, 31)),
+        vec![date(2021, 2, 28), date(2021, 3, 31), date(2021, 4, 30)]
+    );
+}
+
+#[test]
+fn forecast_combines_future_and_recurring_transactions() {
+    let (client, checking, landlord) = setup_two_accounts();
+    create_transaction(
+        &client,
+        "transaction",
+        &landlord,
+        &checking,
+        1000,
+        date(2021, 9, 1),
+    );
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &landlord,
+        300,
+        date(2021, 9, 3),
+    );
+    client
+        .create_recurring_transaction(&NewRecurringTransaction {
+            name: "Rent",
+            source_account_id: checking.id,
+            destination_account_id: landlord.id,
+            amount: 500,
+            frequency: Frequency::Weekly,
+            start_date: date(2021, 8, 25),
+            end_date: None,
+        })
+        .unwrap();
+
+    let forecast = client
+        .forecast_account_balance(&checking.id, &date(2021, 9, 1), 8)
+        .unwrap();
+
+    assert_eq!(forecast.len(), 9);
+    assert_eq!(forecast[0], balance_on(date(2021, 9, 1), 1000));
+    assert_eq!(forecast[2], balance_on(date(2021, 9, 3), 700));
+    assert_eq!(forecast[7], balance_on(date(2021, 9, 8), 200));
+    assert_eq!(forecast[8], balance_on(date(2021, 9, 9), 200));
+}
+
+#[test]
+fn forecast_finds_the_first_negative_day() {
+    let (client, checking, landlord) = setup_two_accounts();
+    create_transaction(
+        &client,
+        "transaction",
+        &landlord,
+        &checking,
+        1000,
+        date(2021, 9, 1),
+    );
+    client
+        .create_recurring_transaction(&NewRecurringTransaction {
+            name: "Rent",
+            source_account_id: checking.id,
+            destination_account_id: landlord.id,
+            amount: 600,
+            frequency: Frequency::Monthly,
+            start_date: date(2021, 9, 5),
+            end_date: None,
+        })
+        .unwrap();
+
+    let forecast = client
+        .forecast_account_balance(&checking.id, &date(2021, 9, 1), 90)
+        .unwrap();
+
+    assert_eq!(
+        first_negative_day(&forecast),
+        Some(&balance_on(date(2021, 10, 5), -200))
+    );
+}
+
+// Helper functions
+
+fn balance_on(date: NaiveDate, balance: i64) -> DailyBalance {
+    DailyBalance { date, balance }
+}
+
+fn setup_two_accounts() -> (Client, Account, Account) {
+    let client = Client::new(None).unwrap();
+    let checking = create_account(&client, "Checking", AccountType::Asset);
+    let landlord = create_account(&client, "Landlord", AccountType::Expense);
+
+    (client, checking, landlord)
+}
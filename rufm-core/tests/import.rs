@@ -0,0 +1,165 @@
+use std::{io::Read, path::Path};
+
+use rufm_core::{
+    import::*,
+    models::accounts::{AccountType, NewAccount},
+    *,
+};
+
+#[test]
+fn registry_finds_importers_by_name() {
+    let importers = registry();
+
+    assert_eq!(importers.get("json").unwrap().name(), "json");
+    assert!(importers.get("xml").is_none());
+}
+
+#[test]
+fn registry_detects_format_from_content() {
+    let importers = registry();
+
+    let importer = importers.detect(Path::new("export.dat"), b"a\tb");
+
+    assert_eq!(importer.unwrap().name(), "tsv");
+}
+
+#[test]
+fn registry_prefers_importers_of_the_file_extension() {
+    let importers = registry();
+
+    let importer = importers.detect(Path::new("notes.txt"), b"{}");
+
+    assert_eq!(importer.unwrap().name(), "text");
+}
+
+#[test]
+fn registry_does_not_detect_unrecognized_content() {
+    let importers = registry();
+
+    assert!(importers.detect(Path::new("export.json"), b"").is_none());
+}
+
+#[test]
+fn sampled_report_counts_the_entries_it_leaves_out() {
+    let mut report = ImportReport::sampled(2);
+    let mut record_report = ImportReport::default();
+    for i in 0..3 {
+        record_report.add_duplicate(&format!("duplicate {}", i));
+    }
+    record_report.add_error(Some(4), "invalid amount");
+
+    report.add_duplicate("first");
+    report.append(record_report);
+
+    assert_eq!(report.skipped_duplicates, vec!["first", "duplicate 0"]);
+    assert_eq!(report.counts.skipped_duplicates, 4);
+    assert_eq!(report.errors.len(), 1);
+    assert!(report.to_string().contains("... and 2 more"));
+}
+
+#[test]
+fn counterparty_names_of_a_payee_share_an_account() {
+    let client = Client::new(None).unwrap();
+    let mut report = ImportReport::default();
+
+    let first = client.get_or_create_payee("CARD 1234 AMAZON EU").unwrap();
+    let first_account =
+        get_or_create_payee_account(&client, &mut report, &first, AccountType::Expense).unwrap();
+    let second = client.get_or_create_payee("CARD 5678 AMAZON EU").unwrap();
+    let second_account =
+        get_or_create_payee_account(&client, &mut report, &second, AccountType::Expense).unwrap();
+
+    assert_eq!(first_account, second_account);
+    assert_eq!(first_account.name, "AMAZON EU");
+    assert_eq!(report.created_accounts.len(), 1);
+}
+
+#[test]
+fn accounts_of_another_type_are_not_used_for_a_payee() {
+    let client = Client::new(None).unwrap();
+    let mut report = ImportReport::default();
+    client
+        .create_account(&NewAccount {
+            name: "Checking",
+            account_type: AccountType::Asset,
+            initial_balance: 0,
+        })
+        .unwrap();
+    let checking = client.get_or_create_payee("Checking").unwrap();
+    let amazon = client.get_or_create_payee("AMAZON").unwrap();
+    get_or_create_payee_account(&client, &mut report, &amazon, AccountType::Expense).unwrap();
+
+    let result = get_or_create_payee_account(&client, &mut report, &checking, AccountType::Expense);
+    assert!(matches!(
+        result,
+        Err(PayeeAccountError::AccountTypeMismatch(
+            AccountTypeMismatch {
+                actual: AccountType::Asset,
+                ..
+            }
+        ))
+    ));
+    let result = get_or_create_payee_account(&client, &mut report, &amazon, AccountType::Revenue);
+    assert!(matches!(
+        result,
+        Err(PayeeAccountError::AccountTypeMismatch(
+            AccountTypeMismatch {
+                actual: AccountType::Expense,
+                expected: AccountType::Revenue,
+                ..
+            }
+        ))
+    ));
+}
+
+// Helper functions
+
+struct TestImporter {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    sniff: fn(&[u8]) -> bool,
+}
+
+impl Importer for TestImporter {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        self.extensions
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        (self.sniff)(head)
+    }
+
+    fn import(
+        &self,
+        _client: &Client,
+        _rdr: &mut dyn Read,
+        _account: Option<&str>,
+    ) -> Result<ImportReport, Box<dyn std::error::Error>> {
+        Ok(ImportReport::default())
+    }
+}
+
+fn registry() -> ImporterRegistry {
+    let mut importers = ImporterRegistry::default();
+    importers.register(TestImporter {
+        name: "json",
+        extensions: &["json"],
+        sniff: |head| head.starts_with(b"{"),
+    });
+    importers.register(TestImporter {
+        name: "tsv",
+        extensions: &["tsv"],
+        sniff: |head| head.contains(&b'\t'),
+    });
+    importers.register(TestImporter {
+        name: "text",
+        extensions: &["txt"],
+        sniff: |head| !head.is_empty(),
+    });
+
+    importers
+}
@@ -0,0 +1,143 @@
+use rufm_core::{
+    integrity::*,
+    models::{accounts::*, transactions::*},
+    *,
+};
+
+mod common;
+use common::*;
+
+#[test]
+fn foreign_keys_are_enforced() {
+    let client = Client::new(None).unwrap();
+    let account = create_account(&client, "Checking", AccountType::Asset);
+
+    let result = client.create_transaction(&new_transaction(
+        "transaction",
+        account.id,
+        AccountId(42),
+        100,
+        date(2021, 9, 1),
+    ));
+
+    assert!(result.is_err());
+    assert!(client.list_transactions().unwrap().is_empty());
+}
+
+#[test]
+fn consistent_ledger_has_no_problems() {
+    let client = Client::new(None).unwrap();
+    create_account(&client, "Checking", AccountType::Asset);
+    create_account(&client, "Savings", AccountType::Asset);
+
+    assert!(client.check_integrity().unwrap().is_empty());
+}
+
+#[test]
+fn check_reports_each_kind_of_problem() {
+    let database = TestDatabase::new("check_reports_each_kind_of_problem");
+    let client = database.client();
+    database.execute_unchecked(&[
+        "INSERT INTO accounts (id, name, account_type, initial_balance) VALUES (1, 'Checking', \
+         0, 0), (2, 'Checking', 1, 0), (3, 'Odd', 7, 0)",
+        "INSERT INTO transactions (id, name, source_account_id, destination_account_id, amount, \
+         date) VALUES (1, 'dangling', 1, 9, 100, '2021-09-01'), (2, 'loop', 1, 1, 100, \
+         '2021-09-01'), (3, 'refund', 2, 1, -250, '2021-09-01')",
+    ]);
+
+    let problems = client.check_integrity().unwrap();
+
+    assert_eq!(
+        problems,
+        vec![
+            Problem::DanglingAccountReference {
+                table: "transactions",
+                column: "destination_account_id",
+                row_id: 1,
+                account_id: AccountId(9),
+            },
+            Problem::SameSourceAndDestination {
+                transaction_id: TransactionId(2),
+            },
+            Problem::NonPositiveAmount {
+                transaction_id: TransactionId(3),
+                amount: -250,
+            },
+            Problem::InvalidAccountType {
+                account_id: AccountId(3),
+                account_type: 7,
+            },
+            Problem::DuplicateAccountName {
+                name: "Checking".to_string(),
+                account_ids: vec![AccountId(1), AccountId(2)],
+            },
+        ]
+    );
+}
+
+#[test]
+fn fix_swaps_negative_transactions_and_renames_duplicate_accounts() {
+    let database = TestDatabase::new("fix_swaps_negative_transactions");
+    let client = database.client();
+    database.execute_unchecked(&[
+        "INSERT INTO accounts (id, name, account_type, initial_balance) VALUES (1, 'Checking', \
+         0, 0), (2, 'Checking', 1, 0)",
+        "INSERT INTO transactions (id, name, source_account_id, destination_account_id, amount, \
+         date) VALUES (1, 'refund', 2, 1, -250, '2021-09-01'), (2, 'loop', 1, 1, 100, \
+         '2021-09-01')",
+        "INSERT INTO payees (id, name, default_account_id) VALUES (1, 'Shop', 9)",
+    ]);
+
+    let fixed = client
+        .check_integrity()
+        .unwrap()
+        .iter()
+        .filter(|problem| client.fix_problem(problem).unwrap())
+        .count();
+
+    assert_eq!(fixed, 3);
+    let refund = client.get_transaction_by_id(&TransactionId(1)).unwrap();
+    assert_eq!(refund.amount, 250);
+    assert_eq!(refund.source_account_id, AccountId(1));
+    assert_eq!(refund.destination_account_id, AccountId(2));
+    assert_eq!(
+        client.get_account_by_id(&AccountId(2)).unwrap().name,
+        "Checking (#2)"
+    );
+    assert_eq!(
+        client.get_payee_by_name("Shop").unwrap().default_account_id,
+        None
+    );
+    assert_eq!(
+        client.check_integrity().unwrap(),
+        vec![Problem::SameSourceAndDestination {
+            transaction_id: TransactionId(2),
+        }]
+    );
+}
+
+#[test]
+fn negative_transaction_with_a_missing_account_is_not_fixed() {
+    let database = TestDatabase::new("negative_transaction_with_a_missing_account");
+    let client = database.client();
+    database.execute_unchecked(&[
+        "INSERT INTO accounts (id, name, account_type, initial_balance) VALUES (1, 'Checking', \
+         0, 0)",
+        "INSERT INTO transactions (id, name, source_account_id, destination_account_id, amount, \
+         date) VALUES (1, 'refund', 9, 1, -250, '2021-09-01')",
+    ]);
+    let problem = Problem::NonPositiveAmount {
+        transaction_id: TransactionId(1),
+        amount: -250,
+    };
+
+    assert!(client.check_integrity().unwrap().contains(&problem));
+    assert!(!client.fix_problem(&problem).unwrap());
+    assert_eq!(
+        client
+            .get_transaction_by_id(&TransactionId(1))
+            .unwrap()
+            .amount,
+        -250
+    );
+}
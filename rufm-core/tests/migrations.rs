@@ -0,0 +1,195 @@
+use rufm_core::{migrations::*, models::accounts::*, *};
+
+mod common;
+use common::*;
+
+const LATEST_MIGRATION: &str = "20210902171530";
+
+#[test]
+fn new_database_is_migrated_without_backup() {
+    let database = TestDatabase::new("new_database_is_migrated_without_backup");
+
+    let client = ClientBuilder::new(database.path())
+        .run_migrations(false)
+        .build()
+        .unwrap();
+    let status = client.migration_status().unwrap();
+
+    assert!(status.is_empty());
+    assert_eq!(status.pending.last().unwrap(), LATEST_MIGRATION);
+
+    let report = client.migrate().unwrap();
+
+    assert_eq!(report.migrations, status.pending);
+    assert_eq!(report.backup_path, None);
+    assert!(client.migration_status().unwrap().is_up_to_date());
+}
+
+#[test]
+fn pending_migrations_are_applied_after_a_backup() {
+    let database = TestDatabase::new("pending_migrations_are_applied_after_a_backup");
+    create_account(&database.client(), "Checking", AccountType::Asset);
+    database.execute_unchecked(&[
+        "DROP TABLE recurring_transactions",
+        &format!(
+            "DELETE FROM __diesel_schema_migrations WHERE version = '{}'",
+            LATEST_MIGRATION
+        ),
+    ]);
+
+    let client = ClientBuilder::new(database.path())
+        .run_migrations(false)
+        .build()
+        .unwrap();
+    assert_eq!(
+        client.migration_status().unwrap().pending,
+        vec![LATEST_MIGRATION.to_string()]
+    );
+
+    let report = client.migrate().unwrap();
+
+    assert_eq!(report.migrations, vec![LATEST_MIGRATION.to_string()]);
+    assert!(client.migration_status().unwrap().is_up_to_date());
+    assert!(client.list_recurring_transactions().unwrap().is_empty());
+    let backup_path = report.backup_path.unwrap();
+    let backup = ClientBuilder::new(&backup_path)
+        .run_migrations(false)
+        .build()
+        .unwrap();
+    assert_eq!(backup.list_accounts().unwrap().len(), 1);
+    assert_eq!(backup.migration_status().unwrap().pending.len(), 1);
+    drop(backup);
+    let _ = std::fs::remove_file(&backup_path);
+}
+
+#[test]
+fn read_only_client_refuses_pending_migrations() {
+    let database = TestDatabase::new("read_only_client_refuses_pending_migrations");
+    database.client();
+    database.execute_unchecked(&[&format!(
+        "DELETE FROM __diesel_schema_migrations WHERE version = '{}'",
+        LATEST_MIGRATION
+    )]);
+
+    let result = ClientBuilder::new(database.path()).read_only(true).build();
+
+    assert!(matches!(
+        result,
+        Err(ClientCreationError::MigrationsError(MigrationError::PendingMigrations(versions)))
+            if versions == vec![LATEST_MIGRATION.to_string()]
+    ));
+    let client = ClientBuilder::new(database.path())
+        .read_only(true)
+        .run_migrations(false)
+        .build()
+        .unwrap();
+    assert_eq!(client.migration_status().unwrap().pending.len(), 1);
+}
+
+#[test]
+fn newer_schema_is_refused() {
+    let database = TestDatabase::new("newer_schema_is_refused");
+    database.client();
+    database.execute_unchecked(&[
+        "INSERT INTO __diesel_schema_migrations (version) VALUES ('29991231000000')",
+    ]);
+
+    let result = Client::new(Some(database.path()));
+
+    assert!(matches!(
+        result,
+        Err(ClientCreationError::MigrationsError(MigrationError::NewerSchema(versions)))
+            if versions == vec!["29991231000000".to_string()]
+    ));
+}
+
+#[test]
+fn newer_schema_keeps_its_journal_mode() {
+    let database = TestDatabase::new("newer_schema_keeps_its_journal_mode");
+    ClientBuilder::new(database.path())
+        .wal(false)
+        .build()
+        .unwrap();
+    database.execute_unchecked(&[
+        "INSERT INTO __diesel_schema_migrations (version) VALUES ('29991231000000')",
+    ]);
+
+    assert!(Client::new(Some(database.path())).is_err());
+
+    // The file format version bytes of the header, 1 for a rollback journal
+    // and 2 for a write-ahead log.
+    let header = std::fs::read(database.path()).unwrap();
+    assert_eq!(&header[18..20], &[1, 1]);
+}
+
+#[test]
+fn newer_schema_can_be_opened_to_show_its_status() {
+    let database = TestDatabase::new("newer_schema_can_be_opened_to_show_its_status");
+    database.client();
+    database.execute_unchecked(&[
+        "INSERT INTO __diesel_schema_migrations (version) VALUES ('29991231000000')",
+    ]);
+
+    let client = ClientBuilder::new(database.path())
+        .allow_newer_schema(true)
+        .run_migrations(false)
+        .build()
+        .unwrap();
+    let status = client.migration_status().unwrap();
+
+    assert!(status.pending.is_empty());
+    assert_eq!(status.unknown, vec!["29991231000000".to_string()]);
+    assert!(matches!(
+        client.migrate(),
+        Err(MigrationError::NewerSchema(_))
+    ));
+}
+
+#[test]
+fn known_versions_are_the_embedded_migrations() {
+    let client = Client::new(None).unwrap();
+
+    let status = client.migration_status().unwrap();
+
+    assert_eq!(status.applied, KNOWN_VERSIONS);
+    assert!(status.unknown.is_empty());
+}
+
+#[test]
+fn backups_do_not_overwrite_each_other() {
+    let database = TestDatabase::new("backups_do_not_overwrite_each_other");
+    database.client();
+    database.execute_unchecked(&[
+        "DROP TABLE recurring_transactions",
+        &format!(
+            "DELETE FROM __diesel_schema_migrations WHERE version = '{}'",
+            LATEST_MIGRATION
+        ),
+    ]);
+    // Backups taken during the same seconds as the next one.
+    let now = chrono::Local::now();
+    let taken = (0..3)
+        .map(|seconds| {
+            let timestamp = now + chrono::Duration::seconds(seconds);
+            format!(
+                "{}.{}.bak",
+                database.path(),
+                timestamp.format("%Y%m%d%H%M%S")
+            )
+        })
+        .collect::<Vec<_>>();
+    for backup_path in &taken {
+        std::fs::write(backup_path, b"").unwrap();
+    }
+
+    let client = ClientBuilder::new(database.path())
+        .run_migrations(false)
+        .build()
+        .unwrap();
+    let backup_path = client.migrate().unwrap().backup_path.unwrap();
+
+    assert!(backup_path.ends_with("-1.bak"));
+    for backup_path in taken.iter().chain(Some(&backup_path)) {
+        let _ = std::fs::remove_file(backup_path);
+    }
+}
@@ -0,0 +1,82 @@
+use rufm_core::{
+    models::{accounts::*, categories::*, payees::*, transactions::*},
+    *,
+};
+
+mod common;
+use common::*;
+
+#[test]
+fn normalization_strips_card_numbers_dates_and_references() {
+    assert_eq!(
+        normalize_payee_name("CARD 1234 AMAZON EU 12/08 REF8X2K9Q"),
+        "AMAZON EU"
+    );
+    assert_eq!(normalize_payee_name("CB*4321 CARREFOUR"), "CARREFOUR");
+    assert_eq!(normalize_payee_name("PRLV EDF  CLIENT"), "EDF CLIENT");
+    assert_eq!(normalize_payee_name("7-ELEVEN"), "7-ELEVEN");
+    assert_eq!(normalize_payee_name("  123456 "), "123456");
+}
+
+#[test]
+fn payees_are_shared_by_normalized_names() {
+    let client = Client::new(None).unwrap();
+
+    let first = client.get_or_create_payee("CARD 1234 AMAZON EU").unwrap();
+    let second = client.get_or_create_payee("CARD 5678 Amazon EU").unwrap();
+
+    assert_eq!(first, second);
+    assert_eq!(first.name, "AMAZON EU");
+    assert_eq!(client.list_payees().unwrap().len(), 1);
+}
+
+#[test]
+fn aliases_point_to_their_payee() {
+    let client = Client::new(None).unwrap();
+    let amazon = client
+        .create_payee(&NewPayee {
+            name: "Amazon",
+            default_category_id: None,
+            default_account_id: None,
+        })
+        .unwrap();
+
+    client
+        .add_payee_alias(&amazon.id, "AMZN MKTP 2Y4KL93")
+        .unwrap();
+
+    assert_eq!(
+        client.get_or_create_payee("CARD 1234 AMZN MKTP").unwrap(),
+        amazon
+    );
+    assert_eq!(
+        client.get_aliases_for_payee(&amazon.id).unwrap(),
+        vec!["AMZN MKTP"]
+    );
+}
+
+#[test]
+fn transactions_get_their_payee_default_category() {
+    let client = Client::new(None).unwrap();
+    let checking = create_account(&client, "Checking", AccountType::Asset);
+    let shop = create_account(&client, "Shop", AccountType::Expense);
+    let food = client
+        .create_category(&NewCategory { name: "Food" })
+        .unwrap();
+    let mut bakery = client.get_or_create_payee("BAKERY").unwrap();
+    bakery.default_category_id = Some(food.id);
+    let bakery = client.update_payee_defaults(&bakery).unwrap();
+
+    let transaction = client
+        .create_transaction(&NewTransaction {
+            payee_id: Some(bakery.id),
+            ..new_transaction("Bread", checking.id, shop.id, 250, date(2021, 8, 27))
+        })
+        .unwrap();
+
+    assert_eq!(transaction.category_id, Some(food.id));
+    assert_eq!(
+        client.get_transactions_for_payee(&bakery.id).unwrap().len(),
+        1
+    );
+}
@@ -0,0 +1,419 @@
+use rufm_core::{
+    models::{accounts::*, categories::*, transactions::*},
+    reports::*,
+    *,
+};
+
+mod common;
+use common::*;
+
+#[test]
+fn months_are_parsed_and_displayed() {
+    let month = "2021-08".parse::<Month>().unwrap();
+
+    assert_eq!(Month::new(2021, 8), Some(month));
+    assert_eq!((month.year(), month.month()), (2021, 8));
+    assert_eq!(month.to_string(), "2021-08");
+    assert_eq!(month.last_day(), date(2021, 8, 31));
+    assert_eq!(
+        "2021-12".parse::<Month>().unwrap().next().to_string(),
+        "2022-01"
+    );
+    assert!("2021-13".parse::<Month>().is_err());
+    assert_eq!(Month::new(2021, 13), None);
+    assert_eq!(Month::new(2021, 0), None);
+}
+
+#[test]
+fn income_expense_report_sums_each_month() {
+    let (client, checking, salary, groceries) = setup_accounts();
+    create_transaction(
+        &client,
+        "transaction",
+        &salary,
+        &checking,
+        2000,
+        date(2021, 1, 1),
+    );
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &groceries,
+        300,
+        date(2021, 1, 15),
+    );
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &groceries,
+        200,
+        date(2021, 1, 31),
+    );
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &groceries,
+        500,
+        date(2021, 3, 2),
+    );
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &groceries,
+        100,
+        date(2021, 4, 1),
+    );
+
+    let report = client
+        .get_income_expense_report(&month(2021, 1), &month(2021, 3))
+        .unwrap();
+
+    assert_eq!(
+        report,
+        vec![
+            IncomeExpense {
+                month: month(2021, 1),
+                income: 2000,
+                expense: 500,
+            },
+            IncomeExpense {
+                month: month(2021, 2),
+                income: 0,
+                expense: 0,
+            },
+            IncomeExpense {
+                month: month(2021, 3),
+                income: 0,
+                expense: 500,
+            },
+        ]
+    );
+    assert_eq!(report[0].net(), 1500);
+    assert_eq!(report[0].savings_rate(), Some(0.75));
+    assert_eq!(report[2].savings_rate(), None);
+}
+
+#[test]
+fn income_expense_report_leaves_out_transfers() {
+    let (client, checking, _, _) = setup_accounts();
+    let savings = create_account(&client, "Savings", AccountType::Asset);
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &savings,
+        1000,
+        date(2021, 1, 1),
+    );
+
+    let report = client
+        .get_income_expense_report(&month(2021, 1), &month(2021, 1))
+        .unwrap();
+
+    assert_eq!(report[0].income, 0);
+    assert_eq!(report[0].expense, 0);
+}
+
+#[test]
+fn income_expense_report_leaves_out_missing_accounts() {
+    let database = TestDatabase::new("income_expense_report_leaves_out_missing_accounts");
+    let client = database.client();
+    database.execute_unchecked(&[
+        "INSERT INTO accounts (id, name, account_type, initial_balance) VALUES (1, 'Checking', \
+         0, 0), (2, 'Groceries', 1, 0)",
+        "INSERT INTO transactions (id, name, source_account_id, destination_account_id, amount, \
+         date) VALUES (1, 'salary', 9, 1, 2000, '2021-01-01'), (2, 'groceries', 1, 2, 500, \
+         '2021-01-02'), (3, 'dangling', 9, 8, 100, '2021-01-03')",
+    ]);
+
+    let report = client
+        .get_income_expense_report(&month(2021, 1), &month(2021, 1))
+        .unwrap();
+
+    assert_eq!(report[0].income, 0);
+    assert_eq!(report[0].expense, 500);
+}
+
+#[test]
+fn periods_end_on_sundays_month_ends_and_year_ends() {
+    // 2021-08-04 is a Wednesday.
+    let day = date(2021, 8, 4);
+
+    assert_eq!(Period::Day.end_of(&day), day);
+    assert_eq!(Period::Week.end_of(&day), date(2021, 8, 8));
+    assert_eq!(Period::Month.end_of(&day), date(2021, 8, 31));
+    assert_eq!(Period::Year.end_of(&day), date(2021, 12, 31));
+    assert_eq!(
+        Period::Month.ends_between(&day, &date(2021, 10, 15)),
+        vec![date(2021, 8, 31), date(2021, 9, 30), date(2021, 10, 15)]
+    );
+}
+
+#[test]
+fn net_worth_report_sums_asset_accounts_at_each_period_end() {
+    let (client, checking, salary, groceries) = setup_accounts();
+    let savings = client
+        .create_account(&NewAccount {
+            name: "Savings",
+            account_type: AccountType::Asset,
+            initial_balance: 1000,
+        })
+        .unwrap();
+    create_transaction(
+        &client,
+        "transaction",
+        &salary,
+        &checking,
+        2000,
+        date(2020, 12, 31),
+    );
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &groceries,
+        300,
+        date(2021, 1, 15),
+    );
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &savings,
+        500,
+        date(2021, 2, 1),
+    );
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &groceries,
+        200,
+        date(2021, 3, 2),
+    );
+
+    let report = client
+        .get_net_worth_report(&date(2021, 1, 1), &date(2021, 3, 1), Period::Month)
+        .unwrap();
+
+    assert_eq!(
+        report,
+        vec![
+            NetWorth {
+                date: date(2021, 1, 31),
+                net_worth: 2700,
+            },
+            NetWorth {
+                date: date(2021, 2, 28),
+                net_worth: 2700,
+            },
+            NetWorth {
+                date: date(2021, 3, 1),
+                net_worth: 2700,
+            },
+        ]
+    );
+}
+
+#[test]
+fn spending_report_sorts_expense_accounts_and_compares_with_previous_month() {
+    let (client, checking, salary, groceries) = setup_accounts();
+    let restaurant = create_account(&client, "Restaurant", AccountType::Expense);
+    create_transaction(
+        &client,
+        "transaction",
+        &salary,
+        &checking,
+        2000,
+        date(2021, 8, 1),
+    );
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &groceries,
+        100,
+        date(2021, 7, 10),
+    );
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &restaurant,
+        250,
+        date(2021, 7, 20),
+    );
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &groceries,
+        300,
+        date(2021, 8, 10),
+    );
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &restaurant,
+        100,
+        date(2021, 8, 20),
+    );
+
+    let report = client
+        .get_spending_report(&month(2021, 8), SpendingGroup::ExpenseAccount)
+        .unwrap();
+
+    assert_eq!(report.total, 400);
+    assert_eq!(report.previous_total, 350);
+    assert_eq!(
+        report.spendings,
+        vec![
+            Spending {
+                name: Some("Groceries".to_string()),
+                total: 300,
+                previous_total: 100,
+                share: 0.75,
+            },
+            Spending {
+                name: Some("Restaurant".to_string()),
+                total: 100,
+                previous_total: 250,
+                share: 0.25,
+            },
+        ]
+    );
+    assert_eq!(report.spendings[1].change(), -150);
+}
+
+#[test]
+fn spending_report_groups_uncategorized_transactions() {
+    let (client, checking, _, groceries) = setup_accounts();
+    let food = client
+        .create_category(&NewCategory { name: "Food" })
+        .unwrap();
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &groceries,
+        300,
+        date(2021, 8, 1),
+    );
+    client
+        .create_transaction(&NewTransaction {
+            category_id: Some(food.id),
+            ..new_transaction(
+                "categorized",
+                checking.id,
+                groceries.id,
+                200,
+                date(2021, 8, 2),
+            )
+        })
+        .unwrap();
+
+    let report = client
+        .get_spending_report(&month(2021, 8), SpendingGroup::Category)
+        .unwrap();
+
+    assert_eq!(
+        report
+            .spendings
+            .iter()
+            .map(|spending| (spending.name.as_deref(), spending.total))
+            .collect::<Vec<_>>(),
+        vec![(None, 300), (Some("Food"), 200)]
+    );
+}
+
+#[test]
+fn spending_report_groups_missing_categories_and_payees() {
+    let database = TestDatabase::new("spending_report_groups_missing_categories");
+    let client = database.client();
+    database.execute_unchecked(&[
+        "INSERT INTO accounts (id, name, account_type, initial_balance) VALUES (1, 'Checking', \
+         0, 0), (2, 'Groceries', 1, 0)",
+        "INSERT INTO transactions (id, name, source_account_id, destination_account_id, amount, \
+         date, category_id, payee_id) VALUES (1, 'groceries', 1, 2, 500, '2021-01-02', 7, 8), \
+         (2, 'dangling', 1, 9, 100, '2021-01-03', NULL, NULL)",
+    ]);
+
+    for group in [SpendingGroup::Category, SpendingGroup::Payee] {
+        let report = client.get_spending_report(&month(2021, 1), group).unwrap();
+
+        assert_eq!(report.total, 500);
+        assert_eq!(
+            report.spendings[0].name.as_deref(),
+            Some(UNKNOWN_SPENDING_NAME)
+        );
+    }
+}
+
+#[test]
+fn daily_spending_report_covers_each_day_of_the_month() {
+    let (client, checking, salary, groceries) = setup_accounts();
+    create_transaction(
+        &client,
+        "transaction",
+        &salary,
+        &checking,
+        2000,
+        date(2021, 2, 1),
+    );
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &groceries,
+        300,
+        date(2021, 2, 3),
+    );
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &groceries,
+        200,
+        date(2021, 2, 3),
+    );
+    create_transaction(
+        &client,
+        "transaction",
+        &checking,
+        &groceries,
+        100,
+        date(2021, 3, 1),
+    );
+
+    let report = client.get_daily_spending_report(&month(2021, 2)).unwrap();
+
+    assert_eq!(report.len(), 28);
+    assert_eq!(
+        report[2],
+        DailySpending {
+            date: date(2021, 2, 3),
+            total: 500,
+        }
+    );
+    assert_eq!(report.iter().map(|daily| daily.total).sum::<i64>(), 500);
+}
+
+// Helper functions
+
+fn month(year: i32, month: u32) -> Month {
+    Month::new(year, month).unwrap()
+}
+
+fn setup_accounts() -> (Client, Account, Account, Account) {
+    let client = Client::new(None).unwrap();
+    let checking = create_account(&client, "Checking", AccountType::Asset);
+    let salary = create_account(&client, "Salary", AccountType::Revenue);
+    let groceries = create_account(&client, "Groceries", AccountType::Expense);
+
+    (client, checking, salary, groceries)
+}
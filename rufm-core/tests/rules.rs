@@ -0,0 +1,238 @@
+use rufm_core::{
+    models::{accounts::*, categories::*, rules::*, tags::*},
+    *,
+};
+
+mod common;
+use common::*;
+
+#[test]
+fn rules_apply_to_new_transactions() {
+    let client = Client::new(None).unwrap();
+    let checking = create_account(&client, "Checking", AccountType::Asset);
+    let unknown = create_account(&client, "Unknown", AccountType::Expense);
+    let amazon = create_account(&client, "Amazon", AccountType::Expense);
+    let shopping = client
+        .create_category(&NewCategory { name: "Shopping" })
+        .unwrap();
+    let online = client.create_tag(&NewTag { name: "online" }).unwrap();
+    let rule = client
+        .create_rule(&NewRule {
+            name: "amazon",
+            name_pattern: Some("(?i)amazon"),
+            min_amount: None,
+            max_amount: None,
+            source_account_id: Some(checking.id),
+            set_name: Some("Amazon"),
+            set_destination_account_id: Some(amazon.id),
+            set_category_id: Some(shopping.id),
+        })
+        .unwrap();
+    client.add_tag_to_rule(&rule.id, &online.id).unwrap();
+
+    let transaction = create_transaction(
+        &client,
+        "CARD 1234 AMAZON EU",
+        &checking,
+        &unknown,
+        2599,
+        date(2021, 8, 25),
+    );
+
+    assert_eq!(transaction.name, "Amazon");
+    assert_eq!(transaction.destination_account_id, amazon.id);
+    assert_eq!(transaction.category_id, Some(shopping.id));
+    assert_eq!(
+        client.get_tags_for_transaction(&transaction.id).unwrap(),
+        vec![online]
+    );
+}
+
+#[test]
+fn rules_replace_the_counterparty_of_deposits() {
+    let client = Client::new(None).unwrap();
+    let checking = create_account(&client, "Checking", AccountType::Asset);
+    let savings = create_account(&client, "Savings", AccountType::Asset);
+    let unknown = create_account(&client, "Unknown", AccountType::Revenue);
+    let amazon = create_account(&client, "Amazon", AccountType::Expense);
+    client
+        .create_rule(&NewRule {
+            name: "amazon",
+            name_pattern: Some("(?i)amazon"),
+            min_amount: None,
+            max_amount: None,
+            source_account_id: None,
+            set_name: None,
+            set_destination_account_id: Some(amazon.id),
+            set_category_id: None,
+        })
+        .unwrap();
+
+    let refund = create_transaction(
+        &client,
+        "AMAZON REFUND",
+        &unknown,
+        &checking,
+        1299,
+        date(2021, 8, 25),
+    );
+    let transfer = create_transaction(
+        &client,
+        "Amazon savings",
+        &checking,
+        &savings,
+        5000,
+        date(2021, 8, 25),
+    );
+
+    assert_eq!(refund.source_account_id, amazon.id);
+    assert_eq!(refund.destination_account_id, checking.id);
+    assert_eq!(client.get_account_balance(&checking.id).unwrap(), -3701);
+    assert_eq!(transfer.source_account_id, checking.id);
+    assert_eq!(transfer.destination_account_id, savings.id);
+}
+
+#[test]
+fn rules_match_every_condition() {
+    let client = Client::new(None).unwrap();
+    let checking = create_account(&client, "Checking", AccountType::Asset);
+    let savings = create_account(&client, "Savings", AccountType::Asset);
+    let shop = create_account(&client, "Shop", AccountType::Expense);
+    client
+        .create_rule(&NewRule {
+            name: "small purchases",
+            name_pattern: None,
+            min_amount: Some(100),
+            max_amount: Some(1000),
+            source_account_id: Some(checking.id),
+            set_name: Some("Small purchase"),
+            set_destination_account_id: None,
+            set_category_id: None,
+        })
+        .unwrap();
+
+    let small = create_transaction(&client, "small", &checking, &shop, 500, date(2021, 8, 25));
+    let large = create_transaction(&client, "large", &checking, &shop, 5000, date(2021, 8, 25));
+    let from_savings = create_transaction(
+        &client,
+        "from savings",
+        &savings,
+        &shop,
+        500,
+        date(2021, 8, 25),
+    );
+
+    assert_eq!(small.name, "Small purchase");
+    assert_eq!(large.name, "large");
+    assert_eq!(from_savings.name, "from savings");
+}
+
+#[test]
+fn later_rules_override_earlier_ones() {
+    let client = Client::new(None).unwrap();
+    let checking = create_account(&client, "Checking", AccountType::Asset);
+    let shop = create_account(&client, "Shop", AccountType::Expense);
+    for (name, set_name) in &[("first", "First"), ("second", "Second")] {
+        client
+            .create_rule(&NewRule {
+                name,
+                name_pattern: Some("^CARD"),
+                min_amount: None,
+                max_amount: None,
+                source_account_id: None,
+                set_name: Some(set_name),
+                set_destination_account_id: None,
+                set_category_id: None,
+            })
+            .unwrap();
+    }
+
+    let transaction =
+        create_transaction(&client, "CARD 42", &checking, &shop, 100, date(2021, 8, 25));
+
+    assert_eq!(transaction.name, "Second");
+}
+
+#[test]
+fn rules_can_be_applied_to_existing_transactions() {
+    let client = Client::new(None).unwrap();
+    let checking = create_account(&client, "Checking", AccountType::Asset);
+    let shop = create_account(&client, "Shop", AccountType::Expense);
+    let transaction =
+        create_transaction(&client, "CARD 42", &checking, &shop, 100, date(2021, 8, 25));
+    client
+        .create_rule(&NewRule {
+            name: "card",
+            name_pattern: Some("^CARD"),
+            min_amount: None,
+            max_amount: None,
+            source_account_id: None,
+            set_name: Some("Card payment"),
+            set_destination_account_id: None,
+            set_category_id: None,
+        })
+        .unwrap();
+
+    let applied = client.apply_rules(&transaction.id).unwrap();
+
+    assert_eq!(applied.len(), 1);
+    assert_eq!(
+        client.get_transaction_by_id(&transaction.id).unwrap().name,
+        "Card payment"
+    );
+}
+
+#[test]
+fn rules_of_a_dry_run_are_not_applied_afterwards() {
+    let client = Client::new(None).unwrap();
+    let checking = create_account(&client, "Checking", AccountType::Asset);
+    let shop = create_account(&client, "Shop", AccountType::Expense);
+
+    let renamed = client
+        .dry_run::<_, RuleError, _>(|| {
+            client.create_rule(&NewRule {
+                name: "card",
+                name_pattern: Some("^CARD"),
+                min_amount: None,
+                max_amount: None,
+                source_account_id: None,
+                set_name: Some("Card payment"),
+                set_destination_account_id: None,
+                set_category_id: None,
+            })?;
+
+            Ok(create_transaction(
+                &client,
+                "CARD 42",
+                &checking,
+                &shop,
+                100,
+                date(2021, 8, 25),
+            ))
+        })
+        .unwrap();
+    let transaction =
+        create_transaction(&client, "CARD 43", &checking, &shop, 100, date(2021, 8, 25));
+
+    assert_eq!(renamed.name, "Card payment");
+    assert_eq!(transaction.name, "CARD 43");
+}
+
+#[test]
+fn invalid_name_patterns_are_rejected() {
+    let client = Client::new(None).unwrap();
+
+    let result = client.create_rule(&NewRule {
+        name: "invalid",
+        name_pattern: Some("(unclosed"),
+        min_amount: None,
+        max_amount: None,
+        source_account_id: None,
+        set_name: Some("Invalid"),
+        set_destination_account_id: None,
+        set_category_id: None,
+    });
+
+    assert!(matches!(result, Err(RuleError::InvalidNamePattern(_))));
+    assert!(client.list_rules().unwrap().is_empty());
+}
@@ -1,5 +1,5 @@
 use rufm_core::{
-    models::{accounts::*, transactions::*},
+    models::{accounts::*, budgets::*, categories::*, tags::*, transactions::*},
     *,
 };
 
@@ -27,6 +27,13 @@ fn can_create_transaction() {
         destination_account_id: destination_account.id,
         amount: 100,
         date: chrono::NaiveDate::from_ymd(1970, 1, 1),
+        external_id: None,
+        category_id: None,
+        budget_id: None,
+        notes: None,
+        payee_id: None,
+        time: None,
+        utc_offset: None,
     };
 
     let actual = client.create_transaction(&expected).unwrap();
@@ -39,3 +46,247 @@ fn can_create_transaction() {
     );
     assert_eq!(expected.amount, actual.amount);
 }
+
+#[test]
+fn can_get_transaction_by_external_id() {
+    let client = Client::new(None).unwrap();
+    let source_account = client
+        .create_account(&NewAccount {
+            name: "source",
+            account_type: AccountType::Asset,
+            initial_balance: 0,
+        })
+        .unwrap();
+    let destination_account = client
+        .create_account(&NewAccount {
+            name: "destination",
+            account_type: AccountType::Asset,
+            initial_balance: 0,
+        })
+        .unwrap();
+
+    let expected = client
+        .create_transaction(&NewTransaction {
+            name: "test",
+            source_account_id: source_account.id,
+            destination_account_id: destination_account.id,
+            amount: 100,
+            date: chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
+            external_id: Some("bank:42"),
+            category_id: None,
+            budget_id: None,
+            notes: None,
+            payee_id: None,
+            time: None,
+            utc_offset: None,
+        })
+        .unwrap();
+
+    let actual = client.get_transaction_by_external_id("bank:42").unwrap();
+    let missing = client
+        .get_transaction_by_external_id("bank:43")
+        .optional()
+        .unwrap();
+
+    assert_eq!(expected.id, actual.id);
+    assert_eq!(Some("bank:42".to_string()), actual.external_id);
+    assert!(missing.is_none());
+}
+
+#[test]
+fn can_create_categorized_transaction() {
+    let client = Client::new(None).unwrap();
+    let source_account = client
+        .create_account(&NewAccount {
+            name: "source",
+            account_type: AccountType::Asset,
+            initial_balance: 0,
+        })
+        .unwrap();
+    let destination_account = client
+        .create_account(&NewAccount {
+            name: "destination",
+            account_type: AccountType::Expense,
+            initial_balance: 0,
+        })
+        .unwrap();
+    let category = client
+        .create_category(&NewCategory { name: "Groceries" })
+        .unwrap();
+    let budget = client.create_budget(&NewBudget { name: "Food" }).unwrap();
+
+    let actual = client
+        .create_transaction(&NewTransaction {
+            name: "test",
+            source_account_id: source_account.id,
+            destination_account_id: destination_account.id,
+            amount: 100,
+            date: chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
+            external_id: None,
+            category_id: Some(category.id),
+            budget_id: Some(budget.id),
+            notes: Some("weekly shopping"),
+            payee_id: None,
+            time: None,
+            utc_offset: None,
+        })
+        .unwrap();
+
+    assert_eq!(Some(category.id), actual.category_id);
+    assert_eq!(Some(budget.id), actual.budget_id);
+    assert_eq!(Some("weekly shopping".to_string()), actual.notes);
+    assert_eq!(category, client.get_category_by_name("Groceries").unwrap());
+    assert_eq!(vec![budget], client.list_budgets().unwrap());
+}
+
+#[test]
+fn can_tag_transaction() {
+    let client = Client::new(None).unwrap();
+    let source_account = client
+        .create_account(&NewAccount {
+            name: "source",
+            account_type: AccountType::Asset,
+            initial_balance: 0,
+        })
+        .unwrap();
+    let destination_account = client
+        .create_account(&NewAccount {
+            name: "destination",
+            account_type: AccountType::Expense,
+            initial_balance: 0,
+        })
+        .unwrap();
+    let transaction = client
+        .create_transaction(&NewTransaction {
+            name: "test",
+            source_account_id: source_account.id,
+            destination_account_id: destination_account.id,
+            amount: 100,
+            date: chrono::NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(),
+            external_id: None,
+            category_id: None,
+            budget_id: None,
+            notes: None,
+            payee_id: None,
+            time: None,
+            utc_offset: None,
+        })
+        .unwrap();
+    let vacation = client.create_tag(&NewTag { name: "vacation" }).unwrap();
+    let food = client.create_tag(&NewTag { name: "food" }).unwrap();
+
+    client
+        .add_tag_to_transaction(&transaction.id, &vacation.id)
+        .unwrap();
+    client
+        .add_tag_to_transaction(&transaction.id, &food.id)
+        .unwrap();
+    client
+        .add_tag_to_transaction(&transaction.id, &food.id)
+        .unwrap();
+
+    assert_eq!(
+        vec![food, vacation],
+        client.get_tags_for_transaction(&transaction.id).unwrap()
+    );
+}
+
+#[test]
+fn transactions_keep_their_time_of_day_and_timezone() {
+    let client = Client::new(None).unwrap();
+    let (source_account, destination_account) = create_two_accounts(&client);
+
+    let actual = client
+        .create_transaction(&NewTransaction {
+            name: "late dinner",
+            source_account_id: source_account.id,
+            destination_account_id: destination_account.id,
+            amount: 100,
+            date: chrono::NaiveDate::from_ymd_opt(2021, 8, 31).unwrap(),
+            external_id: None,
+            category_id: None,
+            budget_id: None,
+            notes: None,
+            payee_id: None,
+            time: chrono::NaiveTime::from_hms_opt(23, 30, 0),
+            utc_offset: Some(2 * 3600),
+        })
+        .unwrap();
+
+    assert_eq!(
+        actual.date_time().unwrap().to_rfc3339(),
+        "2021-08-31T23:30:00+02:00"
+    );
+    assert!(actual.created_at.date() > chrono::NaiveDate::from_ymd_opt(2021, 1, 1).unwrap());
+    assert_eq!(actual.created_at, actual.updated_at);
+}
+
+#[test]
+fn transactions_are_listed_in_a_stable_order() {
+    let client = Client::new(None).unwrap();
+    let (source_account, destination_account) = create_two_accounts(&client);
+    let day = chrono::NaiveDate::from_ymd_opt(2021, 8, 31).unwrap();
+
+    for (name, date, time) in &[
+        ("first untimed", day, None),
+        ("morning", day, chrono::NaiveTime::from_hms_opt(9, 0, 0)),
+        ("second untimed", day, None),
+        ("evening", day, chrono::NaiveTime::from_hms_opt(21, 0, 0)),
+        ("next day", day.succ_opt().unwrap(), None),
+    ] {
+        client
+            .create_transaction(&NewTransaction {
+                name,
+                source_account_id: source_account.id,
+                destination_account_id: destination_account.id,
+                amount: 100,
+                date: *date,
+                external_id: None,
+                category_id: None,
+                budget_id: None,
+                notes: None,
+                payee_id: None,
+                time: *time,
+                utc_offset: None,
+            })
+            .unwrap();
+    }
+
+    let names = client
+        .list_transactions()
+        .unwrap()
+        .into_iter()
+        .map(|transaction| transaction.name)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        vec![
+            "next day",
+            "evening",
+            "morning",
+            "second untimed",
+            "first untimed"
+        ]
+    );
+}
+
+// Helper functions
+
+fn create_two_accounts(client: &Client) -> (Account, Account) {
+    let source_account = client
+        .create_account(&NewAccount {
+            name: "source",
+            account_type: AccountType::Asset,
+            initial_balance: 0,
+        })
+        .unwrap();
+    let destination_account = client
+        .create_account(&NewAccount {
+            name: "destination",
+            account_type: AccountType::Expense,
+            initial_balance: 0,
+        })
+        .unwrap();
+
+    (source_account, destination_account)
+}
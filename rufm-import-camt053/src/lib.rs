@@ -0,0 +1,527 @@
+extern crate rufm_core;
+use std::collections::HashMap;
+
+use roxmltree::{Document, Node};
+use rufm_core::{
+    import::{
+        get_or_create_payee_account,
+        AccountTypeMismatch,
+        ImportReport,
+        Importer,
+        ImporterRegistry,
+        PayeeAccountError,
+        RowError,
+    },
+    models::{
+        accounts::{Account, AccountType, NewAccount},
+        payees::Payee,
+        transactions::{NewTransaction, Transaction},
+    },
+    AccountsRepository,
+    Client,
+    OptionalExtension,
+    PayeesRepository,
+    TransactionsRepository,
+};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ImportCamt053Error {
+    #[error("io error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("xml error: {0}")]
+    XmlError(#[from] roxmltree::Error),
+    #[error("line {0}: missing {1}")]
+    MissingElement(u32, &'static str),
+    #[error("line {0}: invalid amount: {1}")]
+    InvalidAmount(u32, String),
+    #[error("line {0}: invalid date: {1}")]
+    InvalidDate(u32, String),
+    #[error("account not found: {0}")]
+    AccountNotFound(String),
+    #[error(
+        "balance of account '{account}' is {actual} on {date}, but the statement closes at \
+         {expected}{}",
+        entry_errors(.errors)
+    )]
+    BalanceMismatch {
+        account: String,
+        date: chrono::NaiveDate,
+        expected: i64,
+        actual: i64,
+        /// The entries of the statement which could not be imported, which
+        /// likely explain the mismatch.
+        errors: Vec<RowError>,
+    },
+    #[error("{0}")]
+    AccountTypeMismatch(#[from] AccountTypeMismatch),
+    #[error("database error: {0}")]
+    DatabaseError(#[from] rufm_core::QueryError),
+}
+
+impl From<PayeeAccountError> for ImportCamt053Error {
+    fn from(error: PayeeAccountError) -> ImportCamt053Error {
+        match error {
+            PayeeAccountError::AccountTypeMismatch(error) => error.into(),
+            PayeeAccountError::DatabaseError(error) => error.into(),
+        }
+    }
+}
+
+/// A booked `Ntry` of a statement.
+#[derive(Debug)]
+pub struct Entry {
+    /// `NtryRef` or `AcctSvcrRef` of the entry, which banks may leave out.
+    pub reference: Option<String>,
+    pub date: chrono::NaiveDate,
+    /// Signed amount, negative for debits.
+    pub amount: i64,
+    pub remittance_information: Option<String>,
+    pub counterparty_name: Option<String>,
+    pub counterparty_iban: Option<String>,
+}
+
+/// A `Bal` of a statement, such as the opening (`OPBD`) or closing (`CLBD`)
+/// balance.
+#[derive(Debug)]
+pub struct Balance {
+    pub date: chrono::NaiveDate,
+    pub amount: i64,
+}
+
+/// The ISO 20022 CAMT.053 bank statement format.
+pub struct Camt053Importer;
+
+impl Importer for Camt053Importer {
+    fn name(&self) -> &'static str {
+        "camt053"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["xml"]
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        String::from_utf8_lossy(head).contains("camt.053")
+    }
+
+    fn import(
+        &self,
+        client: &Client,
+        rdr: &mut dyn std::io::Read,
+        account: Option<&str>,
+    ) -> Result<ImportReport, Box<dyn std::error::Error>> {
+        Ok(import_camt053(client, rdr, account)?)
+    }
+}
+
+/// Adds the importers of this crate to `importers`.
+pub fn register(importers: &mut ImporterRegistry) {
+    importers.register(Camt053Importer);
+}
+
+/// Lists the entries which could not be imported in a balance mismatch.
+fn entry_errors(errors: &[RowError]) -> String {
+    if errors.is_empty() {
+        return String::new();
+    }
+
+    let errors = errors
+        .iter()
+        .map(|error| format!("\n  {}", error))
+        .collect::<String>();
+    format!(", and these entries could not be imported:{}", errors)
+}
+
+/// Imports every `Stmt` of a CAMT.053 file.
+///
+/// Entries go to `account_name` when given, or else to the account named
+/// after the statement's IBAN, which is created with the statement's opening
+/// balance if needed. Entries whose reference was already imported are
+/// skipped, and the account balance is checked against the statement's
+/// closing balance once its entries are imported.
+///
+/// Each statement is imported in a transaction of its own, so that nothing
+/// is left of a statement whose closing balance does not match. Entries
+/// without a reference are told apart by their date, amount and remittance
+/// information, and their order among the entries sharing them.
+pub fn import_camt053<R: std::io::Read>(
+    client: &Client,
+    mut rdr: R,
+    account_name: Option<&str>,
+) -> Result<ImportReport, ImportCamt053Error> {
+    let mut input = String::new();
+    rdr.read_to_string(&mut input)?;
+    let document = Document::parse(&input)?;
+
+    let mut report = ImportReport::default();
+    for statement in document
+        .descendants()
+        .filter(|node| node.has_tag_name("Stmt"))
+    {
+        let statement_report = client.transaction(|| {
+            let mut statement_report = ImportReport::default();
+            handle_statement(
+                client,
+                &document,
+                statement,
+                account_name,
+                &mut statement_report,
+            )?;
+
+            Ok::<_, ImportCamt053Error>(statement_report)
+        })?;
+        report.append(statement_report);
+    }
+
+    Ok(report)
+}
+
+pub fn handle_statement(
+    client: &Client,
+    document: &Document,
+    statement: Node,
+    account_name: Option<&str>,
+    report: &mut ImportReport,
+) -> Result<(), ImportCamt053Error> {
+    let iban = find_text(statement, &["Acct", "Id", "IBAN"])
+        .or_else(|| find_text(statement, &["Acct", "Id", "Othr", "Id"]))
+        .ok_or_else(|| ImportCamt053Error::MissingElement(line(document, statement), "Acct/Id"))?;
+    let opening_balance = get_balance(document, statement, "OPBD")?;
+    let closing_balance = get_balance(document, statement, "CLBD")?;
+
+    let account = match account_name {
+        Some(account_name) => client
+            .get_account_by_name(account_name)
+            .optional()?
+            .ok_or_else(|| ImportCamt053Error::AccountNotFound(account_name.to_string()))?,
+        None => get_or_create_account(
+            client,
+            report,
+            iban,
+            AccountType::Asset,
+            opening_balance.map_or(0, |balance| balance.amount),
+        )?,
+    };
+
+    let mut occurrences = HashMap::new();
+    for node in statement
+        .children()
+        .filter(|node| node.has_tag_name("Ntry"))
+    {
+        match parse_entry(document, node) {
+            Ok(Some(mut entry)) => {
+                if entry.reference.is_none() {
+                    let digest = entry_digest(&entry);
+                    let occurrence = occurrences.entry(digest).or_insert(0);
+                    *occurrence += 1;
+                    entry.reference = Some(format!("{:016x}-{}", digest, occurrence));
+                }
+
+                match handle_entry(client, &account, iban, &entry, report) {
+                    Err(ImportCamt053Error::AccountTypeMismatch(error)) => {
+                        let line = line(document, node);
+                        report.add_error(Some(line as u64), format!("line {}: {}", line, error))
+                    }
+                    result => result?,
+                }
+            }
+            Ok(None) => {}
+            Err(error) => report.add_error(Some(line(document, node) as u64), error),
+        }
+    }
+
+    if let Some(closing_balance) = closing_balance {
+        let actual = client.get_account_balance_as_of_date(&account.id, &closing_balance.date)?;
+
+        if actual != closing_balance.amount {
+            return Err(ImportCamt053Error::BalanceMismatch {
+                account: account.name,
+                date: closing_balance.date,
+                expected: closing_balance.amount,
+                actual,
+                errors: std::mem::take(&mut report.errors),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn handle_entry(
+    client: &Client,
+    account: &Account,
+    iban: &str,
+    entry: &Entry,
+    report: &mut ImportReport,
+) -> Result<(), ImportCamt053Error> {
+    let external_id = entry
+        .reference
+        .as_ref()
+        .map(|reference| format!("camt053:{}:{}", iban, reference));
+    if let Some(external_id) = &external_id {
+        if client
+            .get_transaction_by_external_id(external_id)
+            .optional()?
+            .is_some()
+        {
+            report.add_duplicate(external_id);
+            return Ok(());
+        }
+    }
+
+    let is_debit = entry.amount < 0;
+    let payee = entry
+        .counterparty_name
+        .as_deref()
+        .map(|name| client.get_or_create_payee(name))
+        .transpose()?;
+    let account_type = if is_debit {
+        AccountType::Expense
+    } else {
+        AccountType::Revenue
+    };
+    let counterparty_account = match &payee {
+        Some(payee) => get_or_create_payee_account(client, report, payee, account_type)?,
+        None => get_or_create_account(
+            client,
+            report,
+            entry
+                .counterparty_iban
+                .as_deref()
+                .unwrap_or("Unknown counterparty"),
+            account_type,
+            0,
+        )?,
+    };
+    let (source_account, destination_account) = if is_debit {
+        (account, &counterparty_account)
+    } else {
+        (&counterparty_account, account)
+    };
+
+    let transaction = create_transaction(
+        client,
+        entry
+            .remittance_information
+            .as_deref()
+            .unwrap_or(&counterparty_account.name),
+        entry,
+        external_id.as_deref(),
+        source_account,
+        destination_account,
+        payee.as_ref(),
+    )?;
+    report.add_transaction(&transaction, source_account, destination_account);
+
+    Ok(())
+}
+
+/// Parses a `Ntry`, returning `None` for entries which are not booked yet.
+pub fn parse_entry(document: &Document, node: Node) -> Result<Option<Entry>, ImportCamt053Error> {
+    let status = find_text(node, &["Sts", "Cd"]).or_else(|| find_text(node, &["Sts"]));
+    if status.is_some_and(|status| status != "BOOK") {
+        return Ok(None);
+    }
+
+    let transaction_details = find(node, &["NtryDtls", "TxDtls"]);
+    let is_debit = parse_is_debit(document, node)?;
+    let (counterparty, counterparty_account) = if is_debit {
+        ("Cdtr", "CdtrAcct")
+    } else {
+        ("Dbtr", "DbtrAcct")
+    };
+
+    let remittance_information = transaction_details
+        .and_then(|details| find(details, &["RmtInf"]))
+        .map(|remittance_information| {
+            remittance_information
+                .children()
+                .filter(|node| node.has_tag_name("Ustrd"))
+                .filter_map(|node| node.text())
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .filter(|remittance_information| !remittance_information.is_empty())
+        .or_else(|| find_text(node, &["AddtlNtryInf"]).map(str::to_string));
+
+    let related_parties = transaction_details.and_then(|details| find(details, &["RltdPties"]));
+    let counterparty_name = related_parties.and_then(|parties| {
+        find_text(parties, &[counterparty, "Nm"])
+            .or_else(|| find_text(parties, &[counterparty, "Pty", "Nm"]))
+    });
+    let counterparty_iban = related_parties
+        .and_then(|parties| find_text(parties, &[counterparty_account, "Id", "IBAN"]));
+
+    let amount = parse_amount(document, node)?;
+
+    Ok(Some(Entry {
+        reference: find_text(node, &["NtryRef"])
+            .or_else(|| find_text(node, &["AcctSvcrRef"]))
+            .map(str::to_string),
+        date: parse_date(document, node, "BookgDt")?,
+        amount: if is_debit { -amount } else { amount },
+        remittance_information,
+        counterparty_name: counterparty_name.map(str::to_string),
+        counterparty_iban: counterparty_iban.map(str::to_string),
+    }))
+}
+
+/// A 64-bit FNV-1a hash of the date, amount and remittance information of
+/// an entry, which unlike `DefaultHasher` does not change between Rust
+/// releases.
+fn entry_digest(entry: &Entry) -> u64 {
+    let content = format!(
+        "{}|{}|{}",
+        entry.date,
+        entry.amount,
+        entry.remittance_information.as_deref().unwrap_or_default()
+    );
+
+    content.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn get_balance(
+    document: &Document,
+    statement: Node,
+    balance_type: &str,
+) -> Result<Option<Balance>, ImportCamt053Error> {
+    let node = statement.children().find(|node| {
+        node.has_tag_name("Bal")
+            && find_text(*node, &["Tp", "CdOrPrtry", "Cd"]) == Some(balance_type)
+    });
+
+    node.map(|node| {
+        let amount = parse_amount(document, node)?;
+
+        Ok(Balance {
+            date: parse_date(document, node, "Dt")?,
+            amount: if parse_is_debit(document, node)? {
+                -amount
+            } else {
+                amount
+            },
+        })
+    })
+    .transpose()
+}
+
+fn parse_amount(document: &Document, node: Node) -> Result<i64, ImportCamt053Error> {
+    let amount = find_text(node, &["Amt"])
+        .ok_or_else(|| ImportCamt053Error::MissingElement(line(document, node), "Amt"))?;
+
+    let (units, decimals) = amount.split_once('.').unwrap_or((amount, ""));
+    let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    if !is_number(units) || !(decimals.is_empty() || is_number(decimals)) || decimals.len() > 2 {
+        return Err(ImportCamt053Error::InvalidAmount(
+            line(document, node),
+            amount.to_string(),
+        ));
+    }
+
+    let units = units
+        .parse::<i64>()
+        .map_err(|_| ImportCamt053Error::InvalidAmount(line(document, node), amount.to_string()))?;
+    let cents = format!("{:0<2}", decimals).parse::<i64>().unwrap_or(0);
+
+    Ok(units * 100 + cents)
+}
+
+fn parse_is_debit(document: &Document, node: Node) -> Result<bool, ImportCamt053Error> {
+    match find_text(node, &["CdtDbtInd"]) {
+        Some("DBIT") => Ok(true),
+        Some("CRDT") => Ok(false),
+        _ => Err(ImportCamt053Error::MissingElement(
+            line(document, node),
+            "CdtDbtInd",
+        )),
+    }
+}
+
+/// Parses a `Dt` or `DtTm` date, keeping only the day.
+fn parse_date(
+    document: &Document,
+    node: Node,
+    element: &'static str,
+) -> Result<chrono::NaiveDate, ImportCamt053Error> {
+    let date = find_text(node, &[element, "Dt"])
+        .or_else(|| find_text(node, &[element, "DtTm"]))
+        .ok_or_else(|| ImportCamt053Error::MissingElement(line(document, node), element))?;
+
+    date.get(..10)
+        .and_then(|day| chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d").ok())
+        .ok_or_else(|| ImportCamt053Error::InvalidDate(line(document, node), date.to_string()))
+}
+
+/// Follows a path of child elements, ignoring namespaces.
+fn find<'a, 'input>(node: Node<'a, 'input>, path: &[&str]) -> Option<Node<'a, 'input>> {
+    path.iter().try_fold(node, |node, name| {
+        node.children().find(|child| child.has_tag_name(*name))
+    })
+}
+
+fn find_text<'a>(node: Node<'a, '_>, path: &[&str]) -> Option<&'a str> {
+    find(node, path)
+        .and_then(|node| node.text())
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+}
+
+fn line(document: &Document, node: Node) -> u32 {
+    document.text_pos_at(node.range().start).row
+}
+
+fn get_or_create_account(
+    client: &Client,
+    report: &mut ImportReport,
+    account_name: &str,
+    account_type: AccountType,
+    initial_balance: i64,
+) -> Result<Account, ImportCamt053Error> {
+    match client.get_account_by_name(account_name).optional()? {
+        Some(account) => Ok(account),
+        None => {
+            let account = client.create_account(&NewAccount {
+                name: account_name,
+                account_type,
+                initial_balance,
+            })?;
+            report.add_account(&account);
+            if initial_balance != 0 {
+                report.add_initial_balance(&account);
+            }
+
+            Ok(account)
+        }
+    }
+}
+
+fn create_transaction(
+    client: &Client,
+    name: &str,
+    entry: &Entry,
+    external_id: Option<&str>,
+    source_account: &Account,
+    destination_account: &Account,
+    payee: Option<&Payee>,
+) -> Result<Transaction, ImportCamt053Error> {
+    client
+        .create_transaction(&NewTransaction {
+            name,
+            amount: entry.amount.abs(),
+            source_account_id: source_account.id,
+            destination_account_id: destination_account.id,
+            date: entry.date,
+            external_id,
+            category_id: None,
+            budget_id: None,
+            notes: None,
+            payee_id: payee.map(|payee| payee.id),
+            time: None,
+            utc_offset: None,
+        })
+        .map_err(|e| e.into())
+}
@@ -0,0 +1,142 @@
+use rufm_core::{
+    models::accounts::{AccountType, NewAccount},
+    *,
+};
+use rufm_import_camt053::*;
+
+const STATEMENT: &[u8] = include_bytes!("fixtures/statement.xml");
+const WITHOUT_REFERENCES: &[u8] = include_bytes!("fixtures/without-references.xml");
+const IBAN: &str = "FR7630006000011234567890189";
+
+#[test]
+fn can_import_statement_into_iban_account() {
+    let client = Client::new(None).unwrap();
+
+    import_camt053(&client, STATEMENT, None).unwrap();
+
+    let account = client.get_account_by_name(IBAN).unwrap();
+    assert_eq!(account.account_type, AccountType::Asset);
+    assert_eq!(account.initial_balance, 100000);
+    assert_eq!(client.list_transactions().unwrap().len(), 3);
+    assert_eq!(get_balance(&client, IBAN), 294745);
+    assert_eq!(get_balance(&client, "Supermarché Dupont"), 4250);
+    assert_eq!(get_balance(&client, "ACME SA"), -200000);
+    assert_eq!(get_balance(&client, "DE89370400440532013000"), 1005);
+}
+
+#[test]
+fn transactions_are_named_after_remittance_information() {
+    let client = Client::new(None).unwrap();
+
+    import_camt053(&client, STATEMENT, None).unwrap();
+
+    let mut names = client
+        .list_transactions()
+        .unwrap()
+        .into_iter()
+        .map(|transaction| transaction.name)
+        .collect::<Vec<_>>();
+    names.sort();
+
+    assert_eq!(
+        names,
+        vec!["Facture 123", "SEPA transfer", "Salaire août 2021"]
+    );
+}
+
+#[test]
+fn reimport_skips_already_imported_entries() {
+    let client = Client::new(None).unwrap();
+
+    import_camt053(&client, STATEMENT, None).unwrap();
+    import_camt053(&client, STATEMENT, None).unwrap();
+
+    assert_eq!(client.list_transactions().unwrap().len(), 3);
+    assert_eq!(get_balance(&client, IBAN), 294745);
+}
+
+#[test]
+fn can_import_statement_into_given_account() {
+    let client = setup_account("checking", 100000).unwrap();
+
+    import_camt053(&client, STATEMENT, Some("checking")).unwrap();
+
+    assert_eq!(get_balance(&client, "checking"), 294745);
+}
+
+#[test]
+fn import_fails_when_closing_balance_does_not_match() {
+    let client = setup_account("checking", 0).unwrap();
+
+    let result = import_camt053(&client, STATEMENT, Some("checking"));
+
+    assert!(matches!(
+        result,
+        Err(ImportCamt053Error::BalanceMismatch {
+            expected: 294745,
+            actual: 194745,
+            ..
+        })
+    ));
+    assert!(client.list_transactions().unwrap().is_empty());
+    assert!(client.get_account_by_name("ACME SA").is_err());
+}
+
+#[test]
+fn balance_mismatch_lists_the_entries_which_could_not_be_imported() {
+    let client = setup_account("checking", 100000).unwrap();
+    let statement = String::from_utf8(STATEMENT.to_vec())
+        .unwrap()
+        .replace(">2000.00<", ">2000.00.00<");
+
+    let error = import_camt053(&client, statement.as_bytes(), Some("checking")).unwrap_err();
+
+    match &error {
+        ImportCamt053Error::BalanceMismatch { errors, .. } => assert_eq!(errors.len(), 1),
+        error => panic!("unexpected error: {}", error),
+    }
+    assert!(error.to_string().contains("invalid amount: 2000.00.00"));
+}
+
+#[test]
+fn reimport_skips_entries_without_reference() {
+    let client = Client::new(None).unwrap();
+
+    import_camt053(&client, WITHOUT_REFERENCES, None).unwrap();
+    let report = import_camt053(&client, WITHOUT_REFERENCES, None).unwrap();
+
+    assert_eq!(report.skipped_duplicates.len(), 3);
+    assert_eq!(client.list_transactions().unwrap().len(), 3);
+    assert_eq!(get_balance(&client, IBAN), 8950);
+}
+
+#[test]
+fn import_fails_on_unknown_account() {
+    let client = Client::new(None).unwrap();
+
+    let result = import_camt053(&client, STATEMENT, Some("checking"));
+
+    assert!(matches!(
+        result,
+        Err(ImportCamt053Error::AccountNotFound(_))
+    ));
+}
+
+// Helper functions
+
+fn setup_account(name: &str, initial_balance: i64) -> Result<Client, Box<dyn std::error::Error>> {
+    let client = Client::new(None)?;
+    client.create_account(&NewAccount {
+        name,
+        account_type: AccountType::Asset,
+        initial_balance,
+    })?;
+
+    Ok(client)
+}
+
+fn get_balance(client: &Client, account_name: &str) -> i64 {
+    let account = client.get_account_by_name(account_name).unwrap();
+
+    client.get_account_balance(&account.id).unwrap()
+}
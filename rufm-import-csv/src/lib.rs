@@ -0,0 +1,463 @@
+extern crate csv;
+extern crate rufm_core;
+use csv::{ReaderBuilder, StringRecord};
+use rufm_core::{
+    import::{
+        get_or_create_payee_account,
+        AccountTypeMismatch,
+        ImportReport,
+        Importer,
+        ImporterRegistry,
+        PayeeAccountError,
+    },
+    models::{
+        accounts::{Account, AccountType, NewAccount},
+        payees::Payee,
+        transactions::{NewTransaction, Transaction},
+    },
+    AccountsRepository,
+    Client,
+    OptionalExtension,
+    PayeesRepository,
+    TransactionsRepository,
+};
+use thiserror::Error;
+
+mod profile;
+
+pub use profile::{Column, Columns, Profile, ProfileError, SignConvention};
+
+#[derive(Error, Debug)]
+pub enum ImportCsvError {
+    #[error("io error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("csv error: {0}")]
+    CsvError(#[from] csv::Error),
+    #[error("unknown column: {0}")]
+    UnknownColumn(String),
+    #[error("line {0}: missing value in column {1}")]
+    MissingValue(u64, usize),
+    #[error("line {0}: invalid amount: {1}")]
+    InvalidAmount(u64, String),
+    #[error("line {0}: invalid date: {1}")]
+    InvalidDate(u64, String),
+    #[error("account not found: {0}")]
+    AccountNotFound(String),
+    #[error("{0}")]
+    AccountTypeMismatch(#[from] AccountTypeMismatch),
+    #[error("database error: {0}")]
+    DatabaseError(#[from] rufm_core::QueryError),
+}
+
+impl From<PayeeAccountError> for ImportCsvError {
+    fn from(error: PayeeAccountError) -> ImportCsvError {
+        match error {
+            PayeeAccountError::AccountTypeMismatch(error) => error.into(),
+            PayeeAccountError::DatabaseError(error) => error.into(),
+        }
+    }
+}
+
+/// A CSV record, read according to a [`Profile`].
+#[derive(Debug)]
+pub struct Row {
+    pub line: u64,
+    pub date: chrono::NaiveDate,
+    /// Signed amount, negative for debits.
+    pub amount: i64,
+    pub description: Option<String>,
+    pub counterparty: Option<String>,
+}
+
+struct ColumnIndexes {
+    date: usize,
+    amount: Option<usize>,
+    debit: Option<usize>,
+    credit: Option<usize>,
+    description: Option<usize>,
+    counterparty: Option<usize>,
+}
+
+impl ImportCsvError {
+    /// Line of the record the error is about, if any.
+    pub fn line(&self) -> Option<u64> {
+        match self {
+            ImportCsvError::CsvError(error) => error.position().map(|position| position.line()),
+            ImportCsvError::MissingValue(line, _)
+            | ImportCsvError::InvalidAmount(line, _)
+            | ImportCsvError::InvalidDate(line, _) => Some(*line),
+            _ => None,
+        }
+    }
+}
+
+/// The CSV files with the layout described by a [`Profile`].
+pub struct CsvImporter {
+    profile: Profile,
+}
+
+impl CsvImporter {
+    pub fn new(profile: Profile) -> CsvImporter {
+        CsvImporter { profile }
+    }
+}
+
+impl Importer for CsvImporter {
+    fn name(&self) -> &'static str {
+        "csv"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["csv"]
+    }
+
+    /// Whether the first row of `head` can be read with the profile.
+    fn sniff(&self, head: &[u8]) -> bool {
+        read_records(&self.profile, head)
+            .is_ok_and(|records| matches!(records.first(), Some(Ok(_))))
+    }
+
+    fn import(
+        &self,
+        client: &Client,
+        rdr: &mut dyn std::io::Read,
+        account: Option<&str>,
+    ) -> Result<ImportReport, Box<dyn std::error::Error>> {
+        let account = account.ok_or("an account name is required to import CSV files")?;
+
+        Ok(import_csv(client, &self.profile, rdr, account)?)
+    }
+}
+
+/// Adds the importer of the CSV files `profile` describes to `importers`.
+pub fn register(importers: &mut ImporterRegistry, profile: Profile) {
+    importers.register(CsvImporter::new(profile));
+}
+
+/// Reads every row of a CSV file with the layout described by `profile`.
+pub fn read_rows<R: std::io::Read>(profile: &Profile, rdr: R) -> Result<Vec<Row>, ImportCsvError> {
+    read_records(profile, rdr)?.into_iter().collect()
+}
+
+/// Reads every row of a CSV file with the layout described by `profile`,
+/// keeping the rows which could not be read along with their error.
+pub fn read_records<R: std::io::Read>(
+    profile: &Profile,
+    mut rdr: R,
+) -> Result<Vec<Result<Row, ImportCsvError>>, ImportCsvError> {
+    let mut bytes = Vec::new();
+    rdr.read_to_end(&mut bytes)?;
+    let encoding =
+        encoding_rs::Encoding::for_label(profile.encoding.as_bytes()).unwrap_or(encoding_rs::UTF_8);
+    // The reader's line numbers do not account for CRLF line endings.
+    let input = encoding.decode(&bytes).0.replace("\r\n", "\n");
+
+    let skipped_lines = input
+        .split_inclusive('\n')
+        .take(profile.skip_rows)
+        .map(str::len)
+        .sum::<usize>();
+
+    let mut csv_reader = ReaderBuilder::new()
+        .delimiter(profile.delimiter as u8)
+        .has_headers(profile.has_headers)
+        .flexible(true)
+        .from_reader(&input.as_bytes()[skipped_lines..]);
+
+    let headers = if profile.has_headers {
+        Some(csv_reader.headers()?.clone())
+    } else {
+        None
+    };
+    let column_indexes = resolve_columns(&profile.columns, headers.as_ref())?;
+
+    Ok(csv_reader
+        .records()
+        .map(|record| {
+            let record = record?;
+            let line =
+                record.position().map_or(0, |position| position.line()) + profile.skip_rows as u64;
+
+            read_row(profile, &column_indexes, &record, line)
+        })
+        .collect())
+}
+
+/// Imports rows into `account_name`, mapping counterparties to Expense
+/// (debits) or Revenue (credits) accounts.
+pub fn import_rows(
+    client: &Client,
+    rows: &[Row],
+    account_name: &str,
+) -> Result<ImportReport, ImportCsvError> {
+    let account = client
+        .get_account_by_name(account_name)
+        .optional()?
+        .ok_or_else(|| ImportCsvError::AccountNotFound(account_name.to_string()))?;
+
+    let mut report = ImportReport::default();
+    for row in rows {
+        match handle_row(client, &account, row, &mut report) {
+            Err(ImportCsvError::AccountTypeMismatch(error)) => {
+                report.add_error(Some(row.line), format!("line {}: {}", row.line, error))
+            }
+            result => result?,
+        }
+    }
+
+    Ok(report)
+}
+
+/// Imports a CSV file into `account_name`, reporting the rows which could
+/// not be read instead of failing.
+pub fn import_csv<R: std::io::Read>(
+    client: &Client,
+    profile: &Profile,
+    rdr: R,
+    account_name: &str,
+) -> Result<ImportReport, ImportCsvError> {
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    for record in read_records(profile, rdr)? {
+        match record {
+            Ok(row) => rows.push(row),
+            Err(error) => errors.push(error),
+        }
+    }
+
+    let mut report = import_rows(client, &rows, account_name)?;
+    for error in errors {
+        report.add_error(error.line(), error);
+    }
+
+    Ok(report)
+}
+
+pub fn handle_row(
+    client: &Client,
+    account: &Account,
+    row: &Row,
+    report: &mut ImportReport,
+) -> Result<(), ImportCsvError> {
+    let is_debit = row.amount < 0;
+    let counterparty = row.counterparty.as_deref().or(row.description.as_deref());
+    let payee = counterparty
+        .map(|counterparty| client.get_or_create_payee(counterparty))
+        .transpose()?;
+    let account_type = if is_debit {
+        AccountType::Expense
+    } else {
+        AccountType::Revenue
+    };
+    let counterparty_account = match &payee {
+        Some(payee) => get_or_create_payee_account(client, report, payee, account_type)?,
+        None => get_or_create_account(client, report, "Unknown counterparty", account_type)?,
+    };
+    let (source_account, destination_account) = if is_debit {
+        (account, &counterparty_account)
+    } else {
+        (&counterparty_account, account)
+    };
+
+    let transaction = create_transaction(
+        client,
+        row.description
+            .as_deref()
+            .unwrap_or(&counterparty_account.name),
+        row,
+        source_account,
+        destination_account,
+        payee.as_ref(),
+    )?;
+    report.add_transaction(&transaction, source_account, destination_account);
+
+    Ok(())
+}
+
+/// Currency symbols amounts may be written with.
+const CURRENCY_SYMBOLS: &[char] = &[
+    '$', '€', '£', '¥', '¢', '₣', '₹', '₩', '₪', '₫', '₺', '₽', '₿',
+];
+
+/// Thousands separators, besides spaces, of amounts whose decimal separator
+/// is another character.
+const THOUSANDS_SEPARATORS: &[char] = &['.', ',', '\'', '’'];
+
+/// Parses an amount into cents, ignoring spaces, thousands separators and
+/// currency symbols. Amounts in parentheses, as in `(12.50)`, are negative.
+/// Amounts with any other character, such as a letter, are invalid.
+pub fn parse_amount(value: &str, decimal_separator: char) -> Option<i64> {
+    let value = value.trim();
+    let (is_parenthesized, value) = match value
+        .strip_prefix('(')
+        .and_then(|value| value.strip_suffix(')'))
+    {
+        Some(value) => (true, value),
+        None => (false, value),
+    };
+    if value.contains(['(', ')']) {
+        return None;
+    }
+
+    let mut kept = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() || c == '-' || c == '+' || c == decimal_separator {
+            kept.push(c);
+        } else if !(c.is_whitespace()
+            || THOUSANDS_SEPARATORS.contains(&c)
+            || CURRENCY_SYMBOLS.contains(&c))
+        {
+            return None;
+        }
+    }
+    let value = kept;
+    let (sign, digits) = match value.strip_prefix('-') {
+        Some(digits) => (-1, digits),
+        None => (1, value.strip_prefix('+').unwrap_or(&value)),
+    };
+    if is_parenthesized && digits.len() < value.len() {
+        return None;
+    }
+    let sign = if is_parenthesized { -1 } else { sign };
+    let (units, decimals) = digits.split_once(decimal_separator).unwrap_or((digits, ""));
+
+    let is_number = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if (units.is_empty() && decimals.is_empty())
+        || !is_number(units)
+        || !is_number(decimals)
+        || decimals.len() > 2
+    {
+        return None;
+    }
+
+    let units = if units.is_empty() {
+        0
+    } else {
+        units.parse::<i64>().ok()?
+    };
+    let cents = format!("{:0<2}", decimals).parse::<i64>().ok()?;
+
+    Some(sign * (units * 100 + cents))
+}
+
+fn resolve_columns(
+    columns: &Columns,
+    headers: Option<&StringRecord>,
+) -> Result<ColumnIndexes, ImportCsvError> {
+    let resolve = |column: &Column| match column {
+        Column::Index(index) => Ok(*index),
+        Column::Name(name) => headers
+            .and_then(|headers| headers.iter().position(|header| header.trim() == name))
+            .ok_or_else(|| ImportCsvError::UnknownColumn(name.clone())),
+    };
+    let resolve_optional = |column: &Option<Column>| column.as_ref().map(resolve).transpose();
+
+    Ok(ColumnIndexes {
+        date: resolve(&columns.date)?,
+        amount: resolve_optional(&columns.amount)?,
+        debit: resolve_optional(&columns.debit)?,
+        credit: resolve_optional(&columns.credit)?,
+        description: resolve_optional(&columns.description)?,
+        counterparty: resolve_optional(&columns.counterparty)?,
+    })
+}
+
+fn read_row(
+    profile: &Profile,
+    column_indexes: &ColumnIndexes,
+    record: &StringRecord,
+    line: u64,
+) -> Result<Row, ImportCsvError> {
+    let get = |index: usize| {
+        record
+            .get(index)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    let get_amount = |index: usize| match get(index) {
+        Some(value) => parse_amount(value, profile.decimal_separator)
+            .ok_or_else(|| ImportCsvError::InvalidAmount(line, value.to_string())),
+        None => Ok(0),
+    };
+
+    let date =
+        get(column_indexes.date).ok_or(ImportCsvError::MissingValue(line, column_indexes.date))?;
+    let date = chrono::NaiveDate::parse_from_str(date, &profile.date_format)
+        .map_err(|_| ImportCsvError::InvalidDate(line, date.to_string()))?;
+
+    let amount = match column_indexes.amount {
+        Some(index) => {
+            get(index).ok_or(ImportCsvError::MissingValue(line, index))?;
+            let amount = get_amount(index)?;
+
+            match profile.sign_convention {
+                SignConvention::NegativeIsDebit => amount,
+                SignConvention::PositiveIsDebit => -amount,
+            }
+        }
+        None => {
+            let debit = column_indexes.debit.map(get_amount).transpose()?;
+            let credit = column_indexes.credit.map(get_amount).transpose()?;
+
+            credit.unwrap_or(0).abs() - debit.unwrap_or(0).abs()
+        }
+    };
+
+    Ok(Row {
+        line,
+        date,
+        amount,
+        description: column_indexes.description.and_then(get).map(str::to_string),
+        counterparty: column_indexes
+            .counterparty
+            .and_then(get)
+            .map(str::to_string),
+    })
+}
+
+fn get_or_create_account(
+    client: &Client,
+    report: &mut ImportReport,
+    account_name: &str,
+    account_type: AccountType,
+) -> Result<Account, ImportCsvError> {
+    match client.get_account_by_name(account_name).optional()? {
+        Some(account) => Ok(account),
+        None => {
+            let account = client.create_account(&NewAccount {
+                name: account_name,
+                account_type,
+                initial_balance: 0,
+            })?;
+            report.add_account(&account);
+
+            Ok(account)
+        }
+    }
+}
+
+fn create_transaction(
+    client: &Client,
+    name: &str,
+    row: &Row,
+    source_account: &Account,
+    destination_account: &Account,
+    payee: Option<&Payee>,
+) -> Result<Transaction, ImportCsvError> {
+    client
+        .create_transaction(&NewTransaction {
+            name,
+            amount: row.amount.abs(),
+            source_account_id: source_account.id,
+            destination_account_id: destination_account.id,
+            date: row.date,
+            external_id: None,
+            category_id: None,
+            budget_id: None,
+            notes: None,
+            payee_id: payee.map(|payee| payee.id),
+            time: None,
+            utc_offset: None,
+        })
+        .map_err(|e| e.into())
+}
@@ -0,0 +1,136 @@
+use serde::Deserialize;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ProfileError {
+    #[error("io error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("invalid profile: {0}")]
+    TomlError(#[from] toml::de::Error),
+    #[error("invalid profile: {0}")]
+    InvalidProfile(&'static str),
+}
+
+/// Describes the CSV layout of a bank export.
+///
+/// ```toml
+/// delimiter = ";"
+/// encoding = "windows-1252"
+/// date_format = "%d/%m/%Y"
+/// decimal_separator = ","
+/// skip_rows = 2
+///
+/// [columns]
+/// date = "Date"
+/// description = "Libellé"
+/// debit = "Débit"
+/// credit = "Crédit"
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    /// Any WHATWG encoding label, such as `utf-8` or `windows-1252`.
+    #[serde(default = "default_encoding")]
+    pub encoding: String,
+    /// A `chrono` format string.
+    pub date_format: String,
+    #[serde(default = "default_decimal_separator")]
+    pub decimal_separator: char,
+    /// Number of lines to skip before the header (or the first record).
+    #[serde(default)]
+    pub skip_rows: usize,
+    #[serde(default = "default_has_headers")]
+    pub has_headers: bool,
+    #[serde(default)]
+    pub sign_convention: SignConvention,
+    pub columns: Columns,
+}
+
+/// Which columns hold the fields of a transaction.
+///
+/// The amount is either read from a single signed `amount` column, or from
+/// separate `debit` and `credit` columns. Amounts in parentheses, as in
+/// `(12.50)`, are negative.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Columns {
+    pub date: Column,
+    pub amount: Option<Column>,
+    pub debit: Option<Column>,
+    pub credit: Option<Column>,
+    pub description: Option<Column>,
+    pub counterparty: Option<Column>,
+}
+
+/// A column, referenced by header name or by zero-based index.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Column {
+    Index(usize),
+    Name(String),
+}
+
+/// Sign of the debits in the `amount` column.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignConvention {
+    #[default]
+    NegativeIsDebit,
+    PositiveIsDebit,
+}
+
+impl Profile {
+    pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Profile, ProfileError> {
+        std::fs::read_to_string(path)?.parse()
+    }
+}
+
+impl std::str::FromStr for Profile {
+    type Err = ProfileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let profile: Profile = toml::from_str(s)?;
+
+        if profile.columns.amount.is_none()
+            && profile.columns.debit.is_none()
+            && profile.columns.credit.is_none()
+        {
+            return Err(ProfileError::InvalidProfile(
+                "either an amount column or debit/credit columns are required",
+            ));
+        }
+        if profile.columns.amount.is_some()
+            && (profile.columns.debit.is_some() || profile.columns.credit.is_some())
+        {
+            return Err(ProfileError::InvalidProfile(
+                "an amount column cannot be used along with debit/credit columns",
+            ));
+        }
+        if !profile.delimiter.is_ascii() {
+            return Err(ProfileError::InvalidProfile("delimiter must be ASCII"));
+        }
+        if encoding_rs::Encoding::for_label(profile.encoding.as_bytes()).is_none() {
+            return Err(ProfileError::InvalidProfile("unknown encoding"));
+        }
+
+        Ok(profile)
+    }
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+fn default_encoding() -> String {
+    "utf-8".to_string()
+}
+
+fn default_decimal_separator() -> char {
+    '.'
+}
+
+fn default_has_headers() -> bool {
+    true
+}
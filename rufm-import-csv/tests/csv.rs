@@ -0,0 +1,167 @@
+use rufm_core::{
+    import::Importer,
+    models::accounts::{AccountType, NewAccount},
+    *,
+};
+use rufm_import_csv::*;
+
+const BANK_FR: &[u8] = include_bytes!("fixtures/bank-fr.csv");
+const BANK_FR_PROFILE: &str = include_str!("fixtures/bank-fr.toml");
+const CREDIT_CARD: &[u8] = include_bytes!("fixtures/credit-card.csv");
+const CREDIT_CARD_PROFILE: &str = include_str!("fixtures/credit-card.toml");
+const INVALID_DATE: &[u8] = include_bytes!("fixtures/invalid-date.csv");
+
+#[test]
+fn can_read_rows_with_debit_and_credit_columns() {
+    let profile = BANK_FR_PROFILE.parse::<Profile>().unwrap();
+
+    let rows = read_rows(&profile, BANK_FR).unwrap();
+
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].line, 4);
+    assert_eq!(
+        rows[0].date,
+        chrono::NaiveDate::from_ymd_opt(2021, 8, 2).unwrap()
+    );
+    assert_eq!(rows[0].amount, -4250);
+    assert_eq!(rows[0].description.as_deref(), Some("CB SUPERMARCHE"));
+    assert_eq!(rows[1].amount, 200000);
+}
+
+#[test]
+fn can_import_with_debit_and_credit_columns() {
+    let client = setup_account("checking").unwrap();
+    let profile = BANK_FR_PROFILE.parse::<Profile>().unwrap();
+
+    import_csv(&client, &profile, BANK_FR, "checking").unwrap();
+
+    assert_eq!(client.list_transactions().unwrap().len(), 3);
+    assert_eq!(get_balance(&client, "checking"), 194745);
+    assert_eq!(get_balance(&client, "SUPERMARCHE"), 4250);
+    assert_eq!(get_balance(&client, "VIREMENT ACME"), -200000);
+}
+
+#[test]
+fn can_import_with_signed_amount_column() {
+    let client = setup_account("credit card").unwrap();
+    let profile = CREDIT_CARD_PROFILE.parse::<Profile>().unwrap();
+
+    import_csv(&client, &profile, CREDIT_CARD, "credit card").unwrap();
+
+    assert_eq!(client.list_transactions().unwrap().len(), 3);
+    assert_eq!(get_balance(&client, "credit card"), -124955);
+    assert_eq!(
+        client.get_account_by_name("Refund").unwrap().account_type,
+        AccountType::Revenue
+    );
+}
+
+#[test]
+fn invalid_rows_report_their_line() {
+    let profile = CREDIT_CARD_PROFILE.parse::<Profile>().unwrap();
+
+    let result = read_rows(&profile, INVALID_DATE);
+
+    assert!(matches!(result, Err(ImportCsvError::InvalidDate(2, _))));
+}
+
+#[test]
+fn amounts_with_letters_are_invalid() {
+    let profile = CREDIT_CARD_PROFILE.parse::<Profile>().unwrap();
+
+    let result = read_rows(
+        &profile,
+        &b"2021-08-03,Bookshop,19.99\n2021-08-04,Cafe,1O0\n"[..],
+    );
+
+    assert!(matches!(result, Err(ImportCsvError::InvalidAmount(2, _))));
+}
+
+#[test]
+fn import_reports_invalid_rows() {
+    let client = setup_account("credit card").unwrap();
+    let profile = CREDIT_CARD_PROFILE.parse::<Profile>().unwrap();
+
+    let report = import_csv(&client, &profile, INVALID_DATE, "credit card").unwrap();
+
+    assert_eq!(report.created_transactions.len(), 1);
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].line, Some(2));
+    assert_eq!(client.list_transactions().unwrap().len(), 1);
+}
+
+#[test]
+fn unknown_columns_are_rejected() {
+    let profile = BANK_FR_PROFILE
+        .replace("\"Libellé\"", "\"Description\"")
+        .parse::<Profile>()
+        .unwrap();
+
+    let result = read_rows(&profile, BANK_FR);
+
+    assert!(matches!(result, Err(ImportCsvError::UnknownColumn(_))));
+}
+
+#[test]
+fn profiles_need_an_amount() {
+    let result = "date_format = \"%Y-%m-%d\"\n[columns]\ndate = 0\n".parse::<Profile>();
+
+    assert!(matches!(result, Err(ProfileError::InvalidProfile(_))));
+}
+
+#[test]
+fn can_parse_amounts() {
+    assert_eq!(parse_amount("-1 234,56", ','), Some(-123456));
+    assert_eq!(parse_amount("1,234.56 €", '.'), Some(123456));
+    assert_eq!(parse_amount("+12", '.'), Some(1200));
+    assert_eq!(parse_amount("12.345", '.'), None);
+    assert_eq!(parse_amount("(12.50)", '.'), Some(-1250));
+    assert_eq!(parse_amount(" ($1,234.56) ", '.'), Some(-123456));
+    assert_eq!(parse_amount("(-12.50)", '.'), None);
+    assert_eq!(parse_amount("(12.50", '.'), None);
+    assert_eq!(parse_amount("1'234.50", '.'), Some(123450));
+    assert_eq!(parse_amount("12abc", '.'), None);
+    assert_eq!(parse_amount("1O0", '.'), None);
+    assert_eq!(parse_amount("abc", '.'), None);
+}
+
+#[test]
+fn importer_recognizes_files_of_its_profile() {
+    let importer = CsvImporter::new(BANK_FR_PROFILE.parse::<Profile>().unwrap());
+
+    assert!(importer.sniff(BANK_FR));
+    assert!(!importer.sniff(CREDIT_CARD));
+}
+
+#[test]
+fn importer_imports_into_the_given_account() {
+    let client = setup_account("checking").unwrap();
+    let importer = CsvImporter::new(BANK_FR_PROFILE.parse::<Profile>().unwrap());
+
+    assert!(importer.import(&client, &mut &BANK_FR[..], None).is_err());
+    let report = importer
+        .import(&client, &mut &BANK_FR[..], Some("checking"))
+        .unwrap();
+
+    assert_eq!(report.created_transactions.len(), 3);
+    assert_eq!(get_balance(&client, "checking"), 194745);
+}
+
+// Helper functions
+
+fn setup_account(name: &str) -> Result<Client, Box<dyn std::error::Error>> {
+    let client = Client::new(None)?;
+    client.create_account(&NewAccount {
+        name,
+        account_type: AccountType::Asset,
+        initial_balance: 0,
+    })?;
+
+    Ok(client)
+}
+
+fn get_balance(client: &Client, account_name: &str) -> i64 {
+    let account = client.get_account_by_name(account_name).unwrap();
+
+    client.get_account_balance(&account.id).unwrap()
+}
@@ -0,0 +1,184 @@
+//! Reads accounts and transactions from the `/api/v1` endpoints of a Firefly
+//! III instance.
+
+use serde::de::DeserializeOwned;
+
+use crate::{AccountType, CsvRecord, ImportFireflyIiiError, TransactionType};
+
+#[derive(Debug, Deserialize)]
+struct Page<T> {
+    data: Vec<T>,
+    meta: Meta,
+}
+
+#[derive(Debug, Deserialize)]
+struct Meta {
+    pagination: Pagination,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pagination {
+    current_page: u32,
+    total_pages: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct Resource<T> {
+    attributes: T,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApiAccount {
+    pub name: String,
+    /// `asset`, `expense`, `revenue`, `liabilities`, `initial-balance`...
+    #[serde(rename = "type")]
+    pub account_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiTransactionGroup {
+    transactions: Vec<ApiTransaction>,
+}
+
+/// A split of a transaction group, the API equivalent of an export row.
+#[derive(Debug, Deserialize)]
+struct ApiTransaction {
+    transaction_journal_id: String,
+    #[serde(rename = "type")]
+    transaction_type: TransactionType,
+    date: chrono::DateTime<chrono::FixedOffset>,
+    amount: String,
+    description: String,
+    source_name: String,
+    source_type: AccountType,
+    destination_name: String,
+    destination_type: AccountType,
+    foreign_amount: Option<String>,
+    foreign_currency_code: Option<String>,
+    category_name: Option<String>,
+    budget_name: Option<String>,
+    bill_name: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    notes: Option<String>,
+}
+
+/// The accounts and transactions of a Firefly III instance, fetched before
+/// any of them is imported.
+#[derive(Debug)]
+pub struct ApiExport {
+    pub accounts: Vec<ApiAccount>,
+    /// Export records, oldest first.
+    pub transactions: Vec<CsvRecord>,
+}
+
+/// A Firefly III instance, authenticated with a personal access token.
+pub struct FireflyIiiApi {
+    base_url: String,
+    token: String,
+    agent: ureq::Agent,
+}
+
+impl FireflyIiiApi {
+    pub fn new(base_url: &str, token: &str) -> FireflyIiiApi {
+        FireflyIiiApi {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            agent: ureq::AgentBuilder::new().build(),
+        }
+    }
+
+    /// Fetches every account and transaction, so that importing them does
+    /// not wait for the network.
+    pub fn export(&self) -> Result<ApiExport, ImportFireflyIiiError> {
+        Ok(ApiExport {
+            accounts: self.list_accounts()?,
+            transactions: self.list_transactions()?,
+        })
+    }
+
+    pub fn list_accounts(&self) -> Result<Vec<ApiAccount>, ImportFireflyIiiError> {
+        Ok(self
+            .get_all::<Resource<ApiAccount>>("accounts")?
+            .into_iter()
+            .map(|account| account.attributes)
+            .collect())
+    }
+
+    /// Lists the splits of every transaction group as export records, oldest
+    /// first.
+    pub fn list_transactions(&self) -> Result<Vec<CsvRecord>, ImportFireflyIiiError> {
+        let mut records = self
+            .get_all::<Resource<ApiTransactionGroup>>("transactions")?
+            .into_iter()
+            .flat_map(|group| group.attributes.transactions)
+            .map(api_transaction_to_record)
+            .collect::<Result<Vec<_>, _>>()?;
+        records.sort_by_key(|record| (record.date, record.journal_id));
+
+        Ok(records)
+    }
+
+    /// Fetches every page of a paginated endpoint.
+    fn get_all<T: DeserializeOwned>(&self, path: &str) -> Result<Vec<T>, ImportFireflyIiiError> {
+        let url = format!("{}/api/v1/{}", self.base_url, path);
+        let mut data = Vec::new();
+        let mut page_number = 1;
+
+        loop {
+            let page = self
+                .agent
+                .get(&url)
+                .set("Authorization", &format!("Bearer {}", self.token))
+                .set("Accept", "application/vnd.api+json")
+                .query("page", &page_number.to_string())
+                .call()
+                .map_err(Box::new)?
+                .into_json::<Page<T>>()?;
+            data.extend(page.data);
+
+            if page.meta.pagination.current_page >= page.meta.pagination.total_pages {
+                return Ok(data);
+            }
+            page_number = page.meta.pagination.current_page + 1;
+        }
+    }
+}
+
+fn api_transaction_to_record(
+    transaction: ApiTransaction,
+) -> Result<CsvRecord, ImportFireflyIiiError> {
+    let amount = transaction.amount.parse::<f64>().map_err(|_| {
+        ImportFireflyIiiError::InvalidRecord(transaction.description.clone(), "invalid amount")
+    })?;
+    let foreign_amount = transaction
+        .foreign_amount
+        .as_deref()
+        .map(|foreign_amount| foreign_amount.parse::<f64>())
+        .transpose()
+        .map_err(|_| {
+            ImportFireflyIiiError::InvalidRecord(
+                transaction.description.clone(),
+                "invalid foreign amount",
+            )
+        })?;
+
+    Ok(CsvRecord {
+        journal_id: transaction.transaction_journal_id.parse().ok(),
+        transaction_type: transaction.transaction_type,
+        amount,
+        description: transaction.description,
+        date: transaction.date,
+        source_name: transaction.source_name,
+        source_type: transaction.source_type,
+        destination_name: transaction.destination_name,
+        destination_type: transaction.destination_type,
+        foreign_amount,
+        foreign_currency_code: transaction.foreign_currency_code,
+        category: transaction.category_name,
+        budget: transaction.budget_name,
+        bill: transaction.bill_name,
+        tags: Some(transaction.tags.join(",")).filter(|tags| !tags.is_empty()),
+        notes: transaction.notes,
+    })
+}
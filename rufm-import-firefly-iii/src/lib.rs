@@ -2,32 +2,57 @@ extern crate csv;
 extern crate rufm_core;
 #[macro_use]
 extern crate serde;
-use csv::Reader;
+use csv::{Reader, StringRecord};
 use rufm_core::{
+    import::{ImportReport, Importer, ImporterRegistry},
     models::{
         accounts::{Account, AccountType as RufmAccountType, NewAccount},
+        budgets::{Budget, NewBudget},
+        categories::{Category, NewCategory},
+        payees::Payee,
+        tags::{NewTag, Tag},
         transactions::{NewTransaction, Transaction},
     },
     AccountsRepository,
+    BudgetsRepository,
+    CategoriesRepository,
     Client,
+    OptionalExtension,
+    PayeesRepository,
+    TagsRepository,
     TransactionsRepository,
 };
 use thiserror::Error;
 
+mod api;
+
+pub use api::{ApiAccount, ApiExport, FireflyIiiApi};
+
 #[derive(Error, Debug)]
 pub enum ImportFireflyIiiError {
+    #[error("io error: {0}")]
+    IoError(#[from] std::io::Error),
     #[error("csv error: {0}")]
     CsvError(#[from] csv::Error),
+    #[error("http error: {0}")]
+    HttpError(#[from] Box<ureq::Error>),
     #[error("database error: {0}")]
     DatabaseError(#[from] rufm_core::QueryError),
+    #[error("line {0}: {1}")]
+    InvalidRow(u64, Box<ImportFireflyIiiError>),
+    #[error("invalid record '{0}': {1}")]
+    InvalidRecord(String, &'static str),
 }
 
 #[derive(Debug, Deserialize)]
 pub enum TransactionType {
+    #[serde(alias = "withdrawal")]
     Withdrawal,
+    #[serde(alias = "deposit")]
     Deposit,
+    #[serde(alias = "transfer")]
     Transfer,
-    #[serde(rename = "Opening balance")]
+    #[serde(rename = "Opening balance", alias = "opening balance")]
     OpeningBalance,
 }
 
@@ -41,79 +66,481 @@ pub enum AccountType {
     Revenue,
     #[serde(rename = "Loan")]
     Loan,
+    #[serde(rename = "Debt")]
+    Debt,
+    #[serde(rename = "Mortgage")]
+    Mortgage,
     #[serde(rename = "Initial balance account")]
     InitialBalance,
 }
 
+impl AccountType {
+    fn is_liability(&self) -> bool {
+        matches!(
+            self,
+            AccountType::Loan | AccountType::Debt | AccountType::Mortgage
+        )
+    }
+
+    /// Whether the account holds money of its own, as opposed to the expense
+    /// and revenue accounts standing for counterparties.
+    fn holds_money(&self) -> bool {
+        matches!(self, AccountType::Asset) || self.is_liability()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CsvRecord {
+    journal_id: Option<u64>,
     #[serde(rename = "type")]
     transaction_type: TransactionType,
     amount: f64,
     description: String,
-    date: chrono::DateTime<chrono::offset::Utc>,
+    date: chrono::DateTime<chrono::FixedOffset>,
     source_name: String,
     source_type: AccountType,
     destination_name: String,
     destination_type: AccountType,
+    foreign_amount: Option<f64>,
+    foreign_currency_code: Option<String>,
+    category: Option<String>,
+    budget: Option<String>,
+    bill: Option<String>,
+    /// Comma separated tag names.
+    tags: Option<String>,
+    notes: Option<String>,
 }
 
+/// Options of [`import_firefly_iii_with_options`].
+pub struct ImportOptions<'a> {
+    /// Number of records imported in each database transaction.
+    pub batch_size: usize,
+    /// Reports invalid records and imports the others, committing each
+    /// batch, instead of failing on the first one and importing nothing.
+    pub skip_invalid_rows: bool,
+    /// Called after each batch with the number of bytes read so far.
+    pub on_progress: Option<&'a mut dyn FnMut(u64)>,
+    /// Number of entries of each kind the report lists, see
+    /// [`ImportReport::sampled`]. All of them by default.
+    pub report_sample_size: Option<usize>,
+}
+
+impl Default for ImportOptions<'_> {
+    fn default() -> Self {
+        ImportOptions {
+            batch_size: 500,
+            skip_invalid_rows: false,
+            on_progress: None,
+            report_sample_size: None,
+        }
+    }
+}
+
+/// Imports a Firefly III CSV export, failing on the first invalid record.
 pub fn import_firefly_iii<R: std::io::Read>(
     client: &Client,
     rdr: R,
+) -> Result<ImportReport, ImportFireflyIiiError> {
+    import_firefly_iii_with_options(client, rdr, ImportOptions::default())
+}
+
+/// Imports a Firefly III CSV export one batch of records at a time.
+///
+/// Firefly III lists the newest transactions first, so the records of each
+/// batch are imported in reverse order, oldest first, and the batches in the
+/// order of the export.
+///
+/// By default, the export is imported in a single database transaction, so
+/// that nothing is imported when a record is invalid. With
+/// `skip_invalid_rows`, each batch is committed on its own, and each invalid
+/// record is rolled back and reported. Batches are only committed with the
+/// transaction the import may run in.
+///
+/// With a `report_sample_size`, memory use does not grow with the size of
+/// the export, apart from the invalid records the report lists.
+pub fn import_firefly_iii_with_options<R: std::io::Read>(
+    client: &Client,
+    rdr: R,
+    options: ImportOptions,
+) -> Result<ImportReport, ImportFireflyIiiError> {
+    if options.skip_invalid_rows {
+        import_batches(client, rdr, options)
+    } else {
+        client.transaction(|| import_batches(client, rdr, options))
+    }
+}
+
+fn import_batches<R: std::io::Read>(
+    client: &Client,
+    rdr: R,
+    mut options: ImportOptions,
+) -> Result<ImportReport, ImportFireflyIiiError> {
+    let mut csv_reader = Reader::from_reader(rdr);
+    let headers = csv_reader.headers()?.clone();
+    let batch_size = options.batch_size.max(1);
+    let mut report = match options.report_sample_size {
+        Some(sample_size) => ImportReport::sampled(sample_size),
+        None => ImportReport::default(),
+    };
+
+    loop {
+        let mut batch = Vec::with_capacity(batch_size);
+        let mut string_record = StringRecord::new();
+        while batch.len() < batch_size && csv_reader.read_record(&mut string_record)? {
+            batch.push(string_record.clone());
+        }
+
+        client.transaction(|| {
+            // Firefly III exports list the newest transactions first.
+            for string_record in batch.iter().rev() {
+                let line = string_record.position().map(|position| position.line());
+
+                let result = string_record
+                    .deserialize::<CsvRecord>(Some(&headers))
+                    .map_err(ImportFireflyIiiError::from)
+                    .and_then(|record| import_record(client, &record, &mut report));
+                match result {
+                    Ok(()) => {}
+                    Err(ImportFireflyIiiError::DatabaseError(error)) => return Err(error.into()),
+                    Err(error) if options.skip_invalid_rows => report.add_error(line, error),
+                    Err(error) => {
+                        return Err(ImportFireflyIiiError::InvalidRow(
+                            line.unwrap_or_default(),
+                            Box::new(error),
+                        ))
+                    }
+                }
+            }
+
+            Ok(())
+        })?;
+
+        if let Some(on_progress) = options.on_progress.as_mut() {
+            on_progress(csv_reader.position().byte());
+        }
+        if batch.len() < batch_size {
+            return Ok(report);
+        }
+    }
+}
+
+/// The Firefly III CSV export format, whose accounts are named in the file.
+pub struct FireflyIiiImporter;
+
+impl Importer for FireflyIiiImporter {
+    fn name(&self) -> &'static str {
+        "firefly-iii"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["csv"]
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        let header = head.split(|byte| *byte == b'\n').next().unwrap_or_default();
+        let header = String::from_utf8_lossy(header);
+
+        header.contains("journal_id") && header.contains("source_name")
+    }
+
+    fn import(
+        &self,
+        client: &Client,
+        rdr: &mut dyn std::io::Read,
+        _account: Option<&str>,
+    ) -> Result<ImportReport, Box<dyn std::error::Error>> {
+        Ok(import_firefly_iii(client, rdr)?)
+    }
+}
+
+/// Adds the importers of this crate to `importers`.
+pub fn register(importers: &mut ImporterRegistry) {
+    importers.register(FireflyIiiImporter);
+}
+
+/// Imports the accounts and transactions of a Firefly III instance through
+/// its REST API, using a personal access token.
+///
+/// Everything is fetched before anything is imported: callers importing in a
+/// database transaction should fetch it with [`FireflyIiiApi::export`] first
+/// and import it with [`import_firefly_iii_api_export`], so that the database
+/// is not locked while the instance answers.
+pub fn import_firefly_iii_api(
+    client: &Client,
+    api: &FireflyIiiApi,
+) -> Result<ImportReport, ImportFireflyIiiError> {
+    import_firefly_iii_api_export(client, &api.export()?)
+}
+
+/// Imports the accounts and transactions fetched from a Firefly III instance.
+///
+/// Accounts are created first, so that accounts without any transaction are
+/// imported as well.
+pub fn import_firefly_iii_api_export(
+    client: &Client,
+    export: &ApiExport,
+) -> Result<ImportReport, ImportFireflyIiiError> {
+    let mut report = ImportReport::default();
+
+    for api_account in &export.accounts {
+        let account_type = match api_account.account_type.as_str() {
+            "asset" => AccountType::Asset,
+            "expense" => AccountType::Expense,
+            "revenue" => AccountType::Revenue,
+            "liabilities" | "liability" => AccountType::Loan,
+            _ => {
+                report.add_warning(format!(
+                    "Skipped {} account '{}'",
+                    api_account.account_type, api_account.name
+                ));
+                continue;
+            }
+        };
+        get_or_create_account(client, &mut report, &api_account.name, &account_type)?;
+    }
+
+    for record in &export.transactions {
+        handle_record_or_report(client, record, &mut report)?;
+    }
+
+    Ok(report)
+}
+
+/// Imports a record in a savepoint, so that an invalid record leaves no
+/// account behind, and adds what it did to `report` once it is imported.
+fn import_record(
+    client: &Client,
+    record: &CsvRecord,
+    report: &mut ImportReport,
 ) -> Result<(), ImportFireflyIiiError> {
-    let mut _csv_reader = Reader::from_reader(rdr);
+    let mut record_report = ImportReport::default();
+    client.transaction(|| handle_record(client, record, &mut record_report))?;
+    report.append(record_report);
 
-    for record in _csv_reader
-        .deserialize()
-        .collect::<Result<Vec<CsvRecord>, csv::Error>>()?
-        .iter()
-        .rev()
+    Ok(())
+}
+
+/// Reports the errors of a single record, only failing on database errors.
+fn handle_record_or_report(
+    client: &Client,
+    record: &CsvRecord,
+    report: &mut ImportReport,
+) -> Result<(), ImportFireflyIiiError> {
+    match import_record(client, record, report) {
+        Err(ImportFireflyIiiError::DatabaseError(error)) => Err(error.into()),
+        Err(error) => {
+            report.add_error(None, error);
+
+            Ok(())
+        }
+        Ok(()) => Ok(()),
+    }
+}
+
+pub fn handle_record(
+    client: &Client,
+    record: &CsvRecord,
+    report: &mut ImportReport,
+) -> Result<(), ImportFireflyIiiError> {
+    match record {
+        CsvRecord {
+            transaction_type: TransactionType::OpeningBalance,
+            source_type: AccountType::InitialBalance,
+            ..
+        } => handle_initial_balance(client, record, report),
+        CsvRecord {
+            transaction_type: TransactionType::OpeningBalance,
+            destination_type: AccountType::InitialBalance,
+            ..
+        } => handle_negative_initial_balance(client, record, report),
+        CsvRecord {
+            transaction_type: TransactionType::OpeningBalance,
+            ..
+        } => Err(ImportFireflyIiiError::InvalidRecord(
+            record.description.clone(),
+            "opening balance without an initial balance account",
+        )),
+        CsvRecord {
+            transaction_type: TransactionType::Withdrawal,
+            ..
+        } => handle_withdrawal(client, record, report),
+        CsvRecord {
+            transaction_type: TransactionType::Deposit,
+            ..
+        } => handle_deposit(client, record, report),
+        CsvRecord {
+            transaction_type: TransactionType::Transfer,
+            ..
+        } => handle_transfer(client, record, report),
+    }
+}
+
+/// Withdrawals move money from an asset account, or a liability, to an
+/// expense account, or to pay back a liability.
+pub fn handle_withdrawal(
+    client: &Client,
+    record: &CsvRecord,
+    report: &mut ImportReport,
+) -> Result<(), ImportFireflyIiiError> {
+    if !record.source_type.holds_money()
+        || !(matches!(record.destination_type, AccountType::Expense)
+            || record.destination_type.is_liability())
     {
-        match record {
-            CsvRecord {
-                transaction_type: TransactionType::OpeningBalance,
-                source_type: AccountType::InitialBalance,
-                ..
-            } => handle_initial_balance(client, record),
-            CsvRecord {
-                transaction_type: TransactionType::OpeningBalance,
-                destination_type: AccountType::InitialBalance,
-                ..
-            } => handle_negative_initial_balance(client, record),
-            CsvRecord {
-                transaction_type: TransactionType::Withdrawal,
-                ..
-            } => handle_withdrawal(client, record),
-            // We assume that every transaction can be treated the same as a withdrawal.
-            _ => handle_withdrawal(client, record),
-        }?;
+        return Err(ImportFireflyIiiError::InvalidRecord(
+            record.description.clone(),
+            "withdrawal which is not from an asset account to an expense account",
+        ));
     }
 
-    Ok(())
+    handle_transaction(client, record, report)
+}
+
+/// Deposits move money from a revenue account, or a liability, to an asset
+/// account or a liability.
+pub fn handle_deposit(
+    client: &Client,
+    record: &CsvRecord,
+    report: &mut ImportReport,
+) -> Result<(), ImportFireflyIiiError> {
+    if !(matches!(record.source_type, AccountType::Revenue) || record.source_type.is_liability())
+        || !record.destination_type.holds_money()
+    {
+        return Err(ImportFireflyIiiError::InvalidRecord(
+            record.description.clone(),
+            "deposit which is not from a revenue account to an asset account",
+        ));
+    }
+
+    handle_transaction(client, record, report)
+}
+
+/// Transfers move money between two asset accounts or liabilities.
+pub fn handle_transfer(
+    client: &Client,
+    record: &CsvRecord,
+    report: &mut ImportReport,
+) -> Result<(), ImportFireflyIiiError> {
+    if !record.source_type.holds_money() || !record.destination_type.holds_money() {
+        return Err(ImportFireflyIiiError::InvalidRecord(
+            record.description.clone(),
+            "transfer which is not between two asset accounts",
+        ));
+    }
+
+    handle_transaction(client, record, report)
 }
 
-pub fn handle_withdrawal(client: &Client, record: &CsvRecord) -> Result<(), ImportFireflyIiiError> {
-    let source_account = get_or_create_account(client, &record.source_name, &record.source_type)?;
-    let destination_account =
-        get_or_create_account(client, &record.destination_name, &record.destination_type)?;
+/// Firefly III records the source and destination of every transaction, but
+/// the sign of its amount depends on the transaction type and the export
+/// version, so the absolute amount always goes from source to destination.
+fn handle_transaction(
+    client: &Client,
+    record: &CsvRecord,
+    report: &mut ImportReport,
+) -> Result<(), ImportFireflyIiiError> {
+    let external_id = record_external_id(record);
+    if let Some(external_id) = &external_id {
+        if is_already_imported(client, external_id)? {
+            report.add_duplicate(external_id);
+            return Ok(());
+        }
+    }
+
+    let payee = match (&record.source_type, &record.destination_type) {
+        (_, AccountType::Expense) => Some(client.get_or_create_payee(&record.destination_name)?),
+        (AccountType::Revenue, _) => Some(client.get_or_create_payee(&record.source_name)?),
+        _ => None,
+    };
+    let source_account = get_or_create_side_account(
+        client,
+        report,
+        payee.as_ref(),
+        &record.source_name,
+        &record.source_type,
+    )?;
+    let destination_account = get_or_create_side_account(
+        client,
+        report,
+        payee.as_ref(),
+        &record.destination_name,
+        &record.destination_type,
+    )?;
+    let category = record
+        .category
+        .as_deref()
+        .map(|category| get_or_create_category(client, category))
+        .transpose()?;
+    let budget = record
+        .budget
+        .as_deref()
+        .map(|budget| get_or_create_budget(client, budget))
+        .transpose()?;
     let transaction = create_transaction(
         client,
-        record.amount,
-        &record.description,
-        &record.date,
+        record,
+        external_id.as_deref(),
         &source_account,
         &destination_account,
+        &Classification {
+            category: category.as_ref(),
+            budget: budget.as_ref(),
+            payee: payee.as_ref(),
+        },
     )?;
+    for tag_name in record_tags(record) {
+        let tag = get_or_create_tag(client, tag_name)?;
+        client.add_tag_to_transaction(&transaction.id, &tag.id)?;
+    }
 
-    println!(
-        "Created transaction '{}' ({}) from '{}' to '{}'",
-        transaction.name, transaction.amount, source_account.name, destination_account.name,
-    );
+    report.add_transaction(&transaction, &source_account, &destination_account);
+    report_unrepresentable_fields(record, report);
 
     Ok(())
 }
 
+fn record_tags(record: &CsvRecord) -> impl Iterator<Item = &str> {
+    record
+        .tags
+        .iter()
+        .flat_map(|tags| tags.split(','))
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+}
+
+/// rufm has no equivalent for bills or foreign currencies, so they are only
+/// reported.
+fn report_unrepresentable_fields(record: &CsvRecord, report: &mut ImportReport) {
+    if let Some(bill) = &record.bill {
+        report.add_warning(format!(
+            "Ignored bill '{}' of transaction '{}'",
+            bill, record.description
+        ));
+    }
+    if let Some(foreign_amount) = record.foreign_amount {
+        report.add_warning(format!(
+            "Ignored foreign amount {} {} of transaction '{}'",
+            foreign_amount,
+            record.foreign_currency_code.as_deref().unwrap_or(""),
+            record.description
+        ));
+    }
+}
+
+/// Firefly III journal IDs identify a transaction across exports, so they
+/// are used to skip records which were already imported.
+fn record_external_id(record: &CsvRecord) -> Option<String> {
+    record
+        .journal_id
+        .map(|journal_id| format!("firefly-iii:{}", journal_id))
+}
+
+fn is_already_imported(client: &Client, external_id: &str) -> Result<bool, ImportFireflyIiiError> {
+    Ok(client
+        .get_transaction_by_external_id(external_id)
+        .optional()?
+        .is_some())
+}
+
 pub fn record_amount_to_rufm_amount(amount: f64) -> i64 {
     (amount * 100.0).round() as i64
 }
@@ -121,17 +548,19 @@ pub fn record_amount_to_rufm_amount(amount: f64) -> i64 {
 pub fn handle_initial_balance(
     client: &Client,
     record: &CsvRecord,
+    report: &mut ImportReport,
 ) -> Result<(), ImportFireflyIiiError> {
-    let mut account =
-        get_or_create_account(client, &record.destination_name, &record.destination_type)?;
+    let mut account = get_or_create_account(
+        client,
+        report,
+        &record.destination_name,
+        &record.destination_type,
+    )?;
 
-    account.initial_balance = record_amount_to_rufm_amount(-record.amount);
+    account.initial_balance = record_amount_to_rufm_amount(record.amount.abs());
 
     let new_account = client.update_account_initial_balance(&account)?;
-    println!(
-        "Updated initial balance of account '{}' to {}",
-        new_account.name, new_account.initial_balance
-    );
+    report.add_initial_balance(&new_account);
 
     Ok(())
 }
@@ -139,16 +568,15 @@ pub fn handle_initial_balance(
 pub fn handle_negative_initial_balance(
     client: &Client,
     record: &CsvRecord,
+    report: &mut ImportReport,
 ) -> Result<(), ImportFireflyIiiError> {
-    let mut account = get_or_create_account(client, &record.source_name, &record.source_type)?;
+    let mut account =
+        get_or_create_account(client, report, &record.source_name, &record.source_type)?;
 
-    account.initial_balance = record_amount_to_rufm_amount(record.amount);
+    account.initial_balance = record_amount_to_rufm_amount(-record.amount.abs());
 
     let new_account = client.update_account_initial_balance(&account)?;
-    println!(
-        "Updated initial balance of account '{}' to {}",
-        new_account.name, new_account.initial_balance
-    );
+    report.add_initial_balance(&new_account);
 
     Ok(())
 }
@@ -160,6 +588,8 @@ impl From<&AccountType> for RufmAccountType {
             AccountType::Expense => RufmAccountType::Expense,
             AccountType::Revenue => RufmAccountType::Revenue,
             AccountType::Loan => RufmAccountType::Asset,
+            AccountType::Debt => RufmAccountType::Asset,
+            AccountType::Mortgage => RufmAccountType::Asset,
             AccountType::InitialBalance => RufmAccountType::Asset,
         }
     }
@@ -167,35 +597,113 @@ impl From<&AccountType> for RufmAccountType {
 
 fn get_or_create_account(
     client: &Client,
+    report: &mut ImportReport,
     account_name: &str,
     account_type: &AccountType,
 ) -> Result<Account, ImportFireflyIiiError> {
-    client.get_account_by_name(account_name).or_else(|_| {
-        client
-            .create_account(&NewAccount {
+    match client.get_account_by_name(account_name).optional()? {
+        Some(account) => Ok(account),
+        None => {
+            let account = client.create_account(&NewAccount {
                 name: account_name,
                 account_type: account_type.into(),
                 initial_balance: 0,
+            })?;
+            report.add_account(&account);
+
+            Ok(account)
+        }
+    }
+}
+
+/// The account of a side of a record: the default account of its payee for
+/// an expense or revenue side of the same type, or else the account the
+/// record names. Only the payee's name is normalized, so that each Firefly III
+/// account keeps its name and its balance.
+fn get_or_create_side_account(
+    client: &Client,
+    report: &mut ImportReport,
+    payee: Option<&Payee>,
+    account_name: &str,
+    account_type: &AccountType,
+) -> Result<Account, ImportFireflyIiiError> {
+    let default_account_id = payee.and_then(|payee| payee.default_account_id);
+    if let (AccountType::Expense | AccountType::Revenue, Some(account_id)) =
+        (account_type, default_account_id)
+    {
+        let account = client.get_account_by_id(&account_id)?;
+        if account.account_type == account_type.into() {
+            return Ok(account);
+        }
+    }
+
+    get_or_create_account(client, report, account_name, account_type)
+}
+
+fn get_or_create_category(
+    client: &Client,
+    category_name: &str,
+) -> Result<Category, ImportFireflyIiiError> {
+    match client.get_category_by_name(category_name).optional()? {
+        Some(category) => Ok(category),
+        None => client
+            .create_category(&NewCategory {
+                name: category_name,
             })
-            .map_err(|e| e.into())
-    })
+            .map_err(|e| e.into()),
+    }
+}
+
+fn get_or_create_budget(
+    client: &Client,
+    budget_name: &str,
+) -> Result<Budget, ImportFireflyIiiError> {
+    match client.get_budget_by_name(budget_name).optional()? {
+        Some(budget) => Ok(budget),
+        None => client
+            .create_budget(&NewBudget { name: budget_name })
+            .map_err(|e| e.into()),
+    }
+}
+
+fn get_or_create_tag(client: &Client, tag_name: &str) -> Result<Tag, ImportFireflyIiiError> {
+    match client.get_tag_by_name(tag_name).optional()? {
+        Some(tag) => Ok(tag),
+        None => client
+            .create_tag(&NewTag { name: tag_name })
+            .map_err(|e| e.into()),
+    }
+}
+
+/// How a transaction is classified, besides its accounts.
+struct Classification<'a> {
+    category: Option<&'a Category>,
+    budget: Option<&'a Budget>,
+    payee: Option<&'a Payee>,
 }
 
 fn create_transaction(
     client: &Client,
-    amount: f64,
-    description: &str,
-    date: &chrono::DateTime<chrono::offset::Utc>,
+    record: &CsvRecord,
+    external_id: Option<&str>,
     source_account: &Account,
     destination_account: &Account,
+    classification: &Classification,
 ) -> Result<Transaction, ImportFireflyIiiError> {
     client
         .create_transaction(&NewTransaction {
-            name: description,
-            amount: (-amount * 100.0).round() as i64,
+            name: &record.description,
+            amount: record_amount_to_rufm_amount(record.amount.abs()),
             source_account_id: source_account.id,
             destination_account_id: destination_account.id,
-            date: date.naive_utc().date(),
+            date: record.date.naive_local().date(),
+            external_id,
+            category_id: classification.category.map(|category| category.id),
+            budget_id: classification.budget.map(|budget| budget.id),
+            notes: record.notes.as_deref(),
+            payee_id: classification.payee.map(|payee| payee.id),
+            time: Some(record.date.time()),
+            utc_offset: Some(record.date.offset().local_minus_utc()),
         })
         .map_err(|e| e.into())
 }
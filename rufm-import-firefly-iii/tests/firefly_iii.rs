@@ -0,0 +1,375 @@
+use rufm_core::{import::Importer, *};
+use rufm_import_firefly_iii::*;
+
+const EXPORT: &[u8] = include_bytes!("fixtures/export.csv");
+const EXPORT_NEXT_WEEK: &[u8] = include_bytes!("fixtures/export-next-week.csv");
+const BALANCES: &[u8] = include_bytes!("fixtures/balances.csv");
+const METADATA: &[u8] = include_bytes!("fixtures/metadata.csv");
+const INVALID: &[u8] = include_bytes!("fixtures/invalid.csv");
+const WRONG_ACCOUNT_TYPES: &[u8] = include_bytes!("fixtures/wrong-account-types.csv");
+const NUMBERED_ACCOUNTS: &[u8] = include_bytes!("fixtures/numbered-accounts.csv");
+
+#[test]
+fn can_import_export() {
+    let client = Client::new(None).unwrap();
+
+    import_firefly_iii(&client, EXPORT).unwrap();
+
+    assert_eq!(client.list_transactions().unwrap().len(), 2);
+    assert_eq!(get_balance(&client, "Checking"), 94745);
+    assert_eq!(get_balance(&client, "Supermarket"), 4250);
+}
+
+#[test]
+fn transactions_keep_their_local_date_and_time() {
+    let client = Client::new(None).unwrap();
+
+    import_firefly_iii(&client, EXPORT).unwrap();
+
+    let bakery = find_transaction(&client, "Bakery");
+    assert_eq!(
+        bakery.date,
+        chrono::NaiveDate::from_ymd_opt(2021, 8, 4).unwrap()
+    );
+    assert_eq!(
+        bakery.date_time().unwrap().to_rfc3339(),
+        "2021-08-04T00:00:00+02:00"
+    );
+}
+
+#[test]
+fn import_reports_what_it_created() {
+    let client = Client::new(None).unwrap();
+
+    let report = import_firefly_iii(&client, EXPORT).unwrap();
+
+    assert_eq!(
+        report
+            .created_accounts
+            .iter()
+            .map(|account| account.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["Checking", "Supermarket", "Bakery"]
+    );
+    assert_eq!(report.initial_balances.len(), 1);
+    assert_eq!(report.initial_balances[0].initial_balance, 100000);
+    assert_eq!(report.created_transactions.len(), 2);
+    assert_eq!(report.created_transactions[0].name, "Groceries");
+    assert!(!report.has_errors());
+}
+
+#[test]
+fn dry_run_reports_without_importing() {
+    let client = Client::new(None).unwrap();
+
+    let report = client
+        .dry_run(|| import_firefly_iii(&client, EXPORT))
+        .unwrap();
+
+    assert_eq!(report.created_transactions.len(), 2);
+    assert!(client.list_transactions().unwrap().is_empty());
+    assert!(client.list_accounts().unwrap().is_empty());
+}
+
+#[test]
+fn reimport_skips_already_imported_transactions() {
+    let client = Client::new(None).unwrap();
+
+    import_firefly_iii(&client, EXPORT).unwrap();
+    let report = import_firefly_iii(&client, EXPORT).unwrap();
+
+    assert!(report.created_transactions.is_empty());
+    assert_eq!(
+        report.skipped_duplicates,
+        vec!["firefly-iii:3", "firefly-iii:4"]
+    );
+    assert_eq!(client.list_transactions().unwrap().len(), 2);
+    assert_eq!(get_balance(&client, "Checking"), 94745);
+}
+
+#[test]
+fn reimport_of_newer_export_only_adds_new_transactions() {
+    let client = Client::new(None).unwrap();
+
+    import_firefly_iii(&client, EXPORT).unwrap();
+    import_firefly_iii(&client, EXPORT_NEXT_WEEK).unwrap();
+
+    let transactions = client.list_transactions().unwrap();
+    assert_eq!(transactions.len(), 3);
+    assert_eq!(
+        transactions
+            .iter()
+            .filter(|transaction| transaction.external_id.as_deref() == Some("firefly-iii:5"))
+            .count(),
+        1
+    );
+    assert_eq!(get_balance(&client, "Checking"), 94425);
+}
+
+#[test]
+fn balances_match_firefly_iii() {
+    let client = Client::new(None).unwrap();
+
+    import_firefly_iii(&client, BALANCES).unwrap();
+
+    // Balances of the exported data, computed by hand.
+    let firefly_iii_balances = [
+        ("Checking", 275750),
+        ("Savings", 520000),
+        ("Credit card", -37000),
+        ("Supermarket", 4250),
+        ("Restaurant", 12000),
+        ("ACME Corp", -200000),
+    ];
+    for (account_name, balance) in firefly_iii_balances.iter() {
+        assert_eq!(
+            get_balance(&client, account_name),
+            *balance,
+            "{}",
+            account_name
+        );
+    }
+}
+
+#[test]
+fn deposits_go_from_revenue_to_asset_account() {
+    let client = Client::new(None).unwrap();
+
+    import_firefly_iii(&client, BALANCES).unwrap();
+
+    let salary = find_transaction(&client, "Salary");
+    assert_eq!(salary.amount, 200000);
+    assert_eq!(
+        salary.source_account_id,
+        client.get_account_by_name("ACME Corp").unwrap().id
+    );
+    assert_eq!(
+        salary.destination_account_id,
+        client.get_account_by_name("Checking").unwrap().id
+    );
+}
+
+#[test]
+fn transfers_go_from_source_to_destination_account() {
+    let client = Client::new(None).unwrap();
+
+    import_firefly_iii(&client, BALANCES).unwrap();
+
+    let to_savings = find_transaction(&client, "To savings");
+    assert_eq!(to_savings.amount, 50000);
+    assert_eq!(
+        to_savings.source_account_id,
+        client.get_account_by_name("Checking").unwrap().id
+    );
+    assert_eq!(
+        to_savings.destination_account_id,
+        client.get_account_by_name("Savings").unwrap().id
+    );
+}
+
+#[test]
+fn categories_budgets_tags_and_notes_are_preserved() {
+    let client = Client::new(None).unwrap();
+
+    import_firefly_iii(&client, METADATA).unwrap();
+
+    let rent = find_transaction(&client, "Rent");
+    assert_eq!(
+        rent.category_id,
+        Some(client.get_category_by_name("Housing").unwrap().id)
+    );
+    assert_eq!(
+        rent.budget_id,
+        Some(client.get_budget_by_name("Monthly").unwrap().id)
+    );
+    assert_eq!(rent.notes.as_deref(), Some("Paid by bank transfer"));
+    assert_eq!(
+        client
+            .get_tags_for_transaction(&rent.id)
+            .unwrap()
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect::<Vec<_>>(),
+        vec!["home", "monthly"]
+    );
+
+    let groceries = find_transaction(&client, "Groceries");
+    assert_eq!(
+        groceries.category_id,
+        Some(client.get_category_by_name("Food").unwrap().id)
+    );
+    assert_eq!(groceries.budget_id, None);
+    assert!(client
+        .get_tags_for_transaction(&groceries.id)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn import_fails_on_first_invalid_row() {
+    let client = Client::new(None).unwrap();
+
+    let error = import_firefly_iii(&client, INVALID).unwrap_err();
+
+    assert!(matches!(error, ImportFireflyIiiError::InvalidRow(3, _)));
+    assert!(client.list_transactions().unwrap().is_empty());
+}
+
+#[test]
+fn failed_import_rolls_back_the_batches_before_the_invalid_row() {
+    let client = Client::new(None).unwrap();
+
+    let options = ImportOptions {
+        batch_size: 1,
+        ..ImportOptions::default()
+    };
+    let result = import_firefly_iii_with_options(&client, INVALID, options);
+
+    assert!(result.is_err());
+    assert!(client.list_transactions().unwrap().is_empty());
+    assert!(client.list_accounts().unwrap().is_empty());
+}
+
+#[test]
+fn import_can_skip_invalid_rows() {
+    let client = Client::new(None).unwrap();
+
+    let options = ImportOptions {
+        skip_invalid_rows: true,
+        ..ImportOptions::default()
+    };
+    let report = import_firefly_iii_with_options(&client, INVALID, options).unwrap();
+
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].line, Some(3));
+    assert_eq!(client.list_transactions().unwrap().len(), 2);
+    assert_eq!(get_balance(&client, "Checking"), 94745);
+    assert!(client.get_account_by_name("Pharmacy").is_err());
+}
+
+#[test]
+fn records_between_unexpected_account_types_are_invalid() {
+    let client = Client::new(None).unwrap();
+
+    let options = ImportOptions {
+        skip_invalid_rows: true,
+        ..ImportOptions::default()
+    };
+    let report = import_firefly_iii_with_options(&client, WRONG_ACCOUNT_TYPES, options).unwrap();
+
+    assert_eq!(report.errors.len(), 2);
+    assert!(client.list_transactions().unwrap().is_empty());
+}
+
+#[test]
+fn accounts_keep_their_firefly_iii_names() {
+    let client = Client::new(None).unwrap();
+
+    import_firefly_iii(&client, NUMBERED_ACCOUNTS).unwrap();
+
+    assert_eq!(get_balance(&client, "Shop 2021"), 2000);
+    assert_eq!(get_balance(&client, "Shop"), 1250);
+    let payee = client.get_payee_by_name("Shop").unwrap();
+    assert!(client
+        .list_transactions()
+        .unwrap()
+        .iter()
+        .all(|transaction| transaction.payee_id == Some(payee.id)));
+}
+
+#[test]
+fn import_reports_progress_after_each_batch() {
+    let client = Client::new(None).unwrap();
+
+    let mut progress = Vec::new();
+    let mut on_progress = |bytes_read| progress.push(bytes_read);
+    let options = ImportOptions {
+        batch_size: 2,
+        on_progress: Some(&mut on_progress),
+        ..ImportOptions::default()
+    };
+    import_firefly_iii_with_options(&client, EXPORT, options).unwrap();
+
+    assert_eq!(progress.len(), 2);
+    assert_eq!(progress[1], EXPORT.len() as u64);
+    assert!(progress[0] < progress[1]);
+    assert_eq!(client.list_transactions().unwrap().len(), 2);
+}
+
+#[test]
+fn import_report_can_be_sampled() {
+    let client = Client::new(None).unwrap();
+
+    let options = ImportOptions {
+        report_sample_size: Some(1),
+        ..ImportOptions::default()
+    };
+    let report = import_firefly_iii_with_options(&client, EXPORT, options).unwrap();
+
+    assert_eq!(report.created_transactions.len(), 1);
+    assert_eq!(report.counts.created_transactions, 2);
+    assert_eq!(report.counts.created_accounts, 3);
+    assert_eq!(client.list_transactions().unwrap().len(), 2);
+}
+
+#[test]
+fn transactions_are_linked_to_payees() {
+    let client = Client::new(None).unwrap();
+    let groceries = client
+        .create_account(&models::accounts::NewAccount {
+            name: "Groceries",
+            account_type: models::accounts::AccountType::Expense,
+            initial_balance: 0,
+        })
+        .unwrap();
+    let mut supermarket = client.get_or_create_payee("Supermarket").unwrap();
+    supermarket.default_account_id = Some(groceries.id);
+    client.update_payee_defaults(&supermarket).unwrap();
+
+    import_firefly_iii(&client, EXPORT).unwrap();
+
+    let transaction = find_transaction(&client, "Groceries");
+    assert_eq!(transaction.payee_id, Some(supermarket.id));
+    assert_eq!(transaction.destination_account_id, groceries.id);
+    assert!(client.get_account_by_name("Supermarket").is_err());
+    let bakery = client.get_payee_by_name("Bakery").unwrap();
+    assert_eq!(
+        find_transaction(&client, "Bakery").payee_id,
+        Some(bakery.id)
+    );
+}
+
+#[test]
+fn importer_recognizes_exports() {
+    assert!(FireflyIiiImporter.sniff(EXPORT));
+    assert!(!FireflyIiiImporter.sniff(b"Date,Label,Amount\n"));
+}
+
+#[test]
+fn importer_imports_exports() {
+    let client = Client::new(None).unwrap();
+
+    let report = FireflyIiiImporter
+        .import(&client, &mut &EXPORT[..], None)
+        .unwrap();
+
+    assert_eq!(report.created_transactions.len(), 2);
+    assert_eq!(get_balance(&client, "Checking"), 94745);
+}
+
+// Helper functions
+
+fn find_transaction(client: &Client, name: &str) -> models::transactions::Transaction {
+    client
+        .list_transactions()
+        .unwrap()
+        .into_iter()
+        .find(|transaction| transaction.name == name)
+        .unwrap()
+}
+
+fn get_balance(client: &Client, account_name: &str) -> i64 {
+    let account = client.get_account_by_name(account_name).unwrap();
+
+    client.get_account_balance(&account.id).unwrap()
+}
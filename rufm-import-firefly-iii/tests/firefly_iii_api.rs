@@ -0,0 +1,146 @@
+use rufm_core::*;
+use rufm_import_firefly_iii::*;
+
+const TOKEN: &str = "personal-access-token";
+const FIXTURES: &[(&str, &str, &str)] = &[
+    (
+        "accounts",
+        "1",
+        include_str!("fixtures/api/accounts-1.json"),
+    ),
+    (
+        "accounts",
+        "2",
+        include_str!("fixtures/api/accounts-2.json"),
+    ),
+    (
+        "accounts",
+        "3",
+        include_str!("fixtures/api/accounts-3.json"),
+    ),
+    (
+        "transactions",
+        "1",
+        include_str!("fixtures/api/transactions-1.json"),
+    ),
+    (
+        "transactions",
+        "2",
+        include_str!("fixtures/api/transactions-2.json"),
+    ),
+];
+
+#[test]
+fn can_import_from_api() {
+    let client = Client::new(None).unwrap();
+    let api = FireflyIiiApi::new(&serve_fixtures(), TOKEN);
+
+    import_firefly_iii_api(&client, &api).unwrap();
+
+    assert_eq!(client.list_transactions().unwrap().len(), 4);
+    assert_eq!(get_balance(&client, "Checking"), 245000);
+    assert_eq!(get_balance(&client, "Savings"), 50000);
+    assert_eq!(get_balance(&client, "Supermarket"), 5000);
+    assert_eq!(get_balance(&client, "ACME Corp"), -200000);
+    assert_eq!(get_balance(&client, "Unused"), 0);
+    assert!(client
+        .get_account_by_name("Checking initial balance")
+        .optional()
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn api_metadata_is_preserved() {
+    let client = Client::new(None).unwrap();
+    let api = FireflyIiiApi::new(&serve_fixtures(), TOKEN);
+
+    import_firefly_iii_api(&client, &api).unwrap();
+
+    let groceries = client
+        .get_transaction_by_external_id("firefly-iii:4")
+        .unwrap();
+    assert_eq!(groceries.name, "Groceries");
+    assert_eq!(
+        groceries.category_id,
+        Some(client.get_category_by_name("Food").unwrap().id)
+    );
+    assert_eq!(groceries.notes.as_deref(), Some("Paid by card"));
+    assert_eq!(
+        client
+            .get_tags_for_transaction(&groceries.id)
+            .unwrap()
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect::<Vec<_>>(),
+        vec!["food", "weekly"]
+    );
+}
+
+#[test]
+fn api_reimport_skips_imported_transactions() {
+    let client = Client::new(None).unwrap();
+    let api = FireflyIiiApi::new(&serve_fixtures(), TOKEN);
+
+    import_firefly_iii_api(&client, &api).unwrap();
+    import_firefly_iii_api(&client, &api).unwrap();
+
+    assert_eq!(client.list_transactions().unwrap().len(), 4);
+    assert_eq!(get_balance(&client, "Checking"), 245000);
+}
+
+#[test]
+fn api_rejects_invalid_token() {
+    let client = Client::new(None).unwrap();
+    let api = FireflyIiiApi::new(&serve_fixtures(), "invalid-token");
+
+    let result = import_firefly_iii_api(&client, &api);
+
+    assert!(matches!(result, Err(ImportFireflyIiiError::HttpError(_))));
+}
+
+// Helper functions
+
+/// Serves the recorded API responses on a local port, like a Firefly III
+/// instance would, and returns its base URL.
+fn serve_fixtures() -> String {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", server.server_addr());
+
+    std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let is_authorized = request.headers().iter().any(|header| {
+                header.field.equiv("Authorization")
+                    && header.value.as_str() == format!("Bearer {}", TOKEN)
+            });
+            let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+            let page = query
+                .split('&')
+                .find_map(|parameter| parameter.strip_prefix("page="))
+                .unwrap_or("1");
+            let fixture = path.strip_prefix("/api/v1/").and_then(|endpoint| {
+                FIXTURES
+                    .iter()
+                    .find(|(name, number, _)| *name == endpoint && *number == page)
+            });
+
+            let response = match fixture {
+                Some((_, _, body)) if is_authorized => {
+                    tiny_http::Response::from_string(*body).with_status_code(200)
+                }
+                Some(_) => tiny_http::Response::from_string("{\"message\":\"Unauthenticated.\"}")
+                    .with_status_code(401),
+                None => tiny_http::Response::from_string("").with_status_code(404),
+            };
+            request.respond(response).unwrap();
+        }
+    });
+
+    base_url
+}
+
+fn get_balance(client: &Client, account_name: &str) -> i64 {
+    let account = client.get_account_by_name(account_name).unwrap();
+
+    client.get_account_balance(&account.id).unwrap()
+}
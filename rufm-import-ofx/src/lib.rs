@@ -0,0 +1,309 @@
+extern crate rufm_core;
+use rufm_core::{
+    import::{
+        get_or_create_payee_account,
+        AccountTypeMismatch,
+        ImportReport,
+        Importer,
+        ImporterRegistry,
+        PayeeAccountError,
+    },
+    models::{
+        accounts::{Account, AccountType},
+        payees::Payee,
+        transactions::{NewTransaction, Transaction},
+    },
+    AccountsRepository,
+    Client,
+    OptionalExtension,
+    PayeesRepository,
+    TransactionsRepository,
+};
+use thiserror::Error;
+
+mod parser;
+
+pub use parser::{Element, ParseError};
+
+#[derive(Error, Debug)]
+pub enum ImportOfxError {
+    #[error("io error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("ofx parse error: {0}")]
+    ParseError(#[from] ParseError),
+    #[error("missing {0} in statement transaction")]
+    MissingField(&'static str),
+    #[error("invalid amount: {0}")]
+    InvalidAmount(String),
+    #[error("invalid date: {0}")]
+    InvalidDate(String),
+    #[error("account not found: {0}")]
+    AccountNotFound(String),
+    #[error("{0}")]
+    AccountTypeMismatch(#[from] AccountTypeMismatch),
+    #[error("database error: {0}")]
+    DatabaseError(#[from] rufm_core::QueryError),
+}
+
+impl From<PayeeAccountError> for ImportOfxError {
+    fn from(error: PayeeAccountError) -> ImportOfxError {
+        match error {
+            PayeeAccountError::AccountTypeMismatch(error) => error.into(),
+            PayeeAccountError::DatabaseError(error) => error.into(),
+        }
+    }
+}
+
+/// A `STMTTRN` entry of a bank or credit card statement.
+#[derive(Debug)]
+pub struct StatementTransaction {
+    pub fit_id: String,
+    pub date: chrono::NaiveDate,
+    pub amount: i64,
+    pub payee: String,
+    pub memo: Option<String>,
+}
+
+impl StatementTransaction {
+    pub fn from_element(element: &Element) -> Result<StatementTransaction, ImportOfxError> {
+        let fit_id = element
+            .child_value("FITID")
+            .ok_or(ImportOfxError::MissingField("FITID"))?;
+        let date = element
+            .child_value("DTPOSTED")
+            .ok_or(ImportOfxError::MissingField("DTPOSTED"))?;
+        let amount = element
+            .child_value("TRNAMT")
+            .ok_or(ImportOfxError::MissingField("TRNAMT"))?;
+        let memo = element.child_value("MEMO");
+        let payee = element
+            .child_value("NAME")
+            .or_else(|| {
+                element
+                    .child("PAYEE")
+                    .and_then(|payee| payee.child_value("NAME"))
+            })
+            .or(memo)
+            .ok_or(ImportOfxError::MissingField("NAME"))?;
+
+        Ok(StatementTransaction {
+            fit_id: fit_id.to_string(),
+            date: parse_date(date).ok_or_else(|| ImportOfxError::InvalidDate(date.to_string()))?,
+            amount: parse_amount(amount)
+                .ok_or_else(|| ImportOfxError::InvalidAmount(amount.to_string()))?,
+            payee: payee.to_string(),
+            memo: memo.map(str::to_string),
+        })
+    }
+}
+
+/// The OFX and QFX statement formats, versions 1 (SGML) and 2 (XML).
+pub struct OfxImporter;
+
+impl Importer for OfxImporter {
+    fn name(&self) -> &'static str {
+        "ofx"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ofx", "qfx"]
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        let head = String::from_utf8_lossy(head);
+
+        head.contains("OFXHEADER") || head.contains("<OFX>")
+    }
+
+    fn import(
+        &self,
+        client: &Client,
+        rdr: &mut dyn std::io::Read,
+        account: Option<&str>,
+    ) -> Result<ImportReport, Box<dyn std::error::Error>> {
+        let account = account.ok_or("an account name is required to import OFX statements")?;
+
+        Ok(import_ofx(client, rdr, account)?)
+    }
+}
+
+/// Adds the importers of this crate to `importers`.
+pub fn register(importers: &mut ImporterRegistry) {
+    importers.register(OfxImporter);
+}
+
+/// Imports every statement transaction of an OFX file into `account_name`.
+///
+/// Counterparties are mapped to Expense (debits) or Revenue (credits)
+/// accounts named after their payee, and transactions whose `FITID` was
+/// already imported are skipped.
+pub fn import_ofx<R: std::io::Read>(
+    client: &Client,
+    mut rdr: R,
+    account_name: &str,
+) -> Result<ImportReport, ImportOfxError> {
+    let mut bytes = Vec::new();
+    rdr.read_to_end(&mut bytes)?;
+    let ofx = parser::parse(&decode(bytes))?;
+
+    let account = client
+        .get_account_by_name(account_name)
+        .optional()?
+        .ok_or_else(|| ImportOfxError::AccountNotFound(account_name.to_string()))?;
+
+    let mut report = ImportReport::default();
+    let statements = ofx
+        .descendants("STMTRS")
+        .into_iter()
+        .chain(ofx.descendants("CCSTMTRS"));
+    for statement in statements {
+        let ofx_account_id = statement
+            .child("BANKACCTFROM")
+            .or_else(|| statement.child("CCACCTFROM"))
+            .and_then(|account_from| account_from.child_value("ACCTID"))
+            .unwrap_or_default();
+
+        for element in statement.descendants("STMTTRN") {
+            match StatementTransaction::from_element(element) {
+                Ok(statement_transaction) => match handle_statement_transaction(
+                    client,
+                    &account,
+                    ofx_account_id,
+                    &statement_transaction,
+                    &mut report,
+                ) {
+                    Err(ImportOfxError::AccountTypeMismatch(error)) => report.add_error(
+                        Some(element.line as u64),
+                        format!("line {}: {}", element.line, error),
+                    ),
+                    result => result?,
+                },
+                Err(error) => report.add_error(
+                    Some(element.line as u64),
+                    format!("line {}: {}", element.line, error),
+                ),
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+pub fn handle_statement_transaction(
+    client: &Client,
+    account: &Account,
+    ofx_account_id: &str,
+    statement_transaction: &StatementTransaction,
+    report: &mut ImportReport,
+) -> Result<(), ImportOfxError> {
+    let external_id = format!("ofx:{}:{}", ofx_account_id, statement_transaction.fit_id);
+    if client
+        .get_transaction_by_external_id(&external_id)
+        .optional()?
+        .is_some()
+    {
+        report.add_duplicate(&external_id);
+        return Ok(());
+    }
+
+    let is_debit = statement_transaction.amount < 0;
+    let payee = client.get_or_create_payee(&statement_transaction.payee)?;
+    let payee_account = get_or_create_payee_account(
+        client,
+        report,
+        &payee,
+        if is_debit {
+            AccountType::Expense
+        } else {
+            AccountType::Revenue
+        },
+    )?;
+    let (source_account, destination_account) = if is_debit {
+        (account, &payee_account)
+    } else {
+        (&payee_account, account)
+    };
+
+    let transaction = create_transaction(
+        client,
+        statement_transaction,
+        &external_id,
+        source_account,
+        destination_account,
+        &payee,
+    )?;
+    report.add_transaction(&transaction, source_account, destination_account);
+
+    Ok(())
+}
+
+/// Parses an OFX amount (`-12.34`, `+5`, `12,3`) into cents.
+pub fn parse_amount(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (sign, digits) = match value.strip_prefix('-') {
+        Some(digits) => (-1, digits),
+        None => (1, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let (units, decimals) = match digits.find(['.', ',']) {
+        Some(index) => (&digits[..index], &digits[index + 1..]),
+        None => (digits, ""),
+    };
+
+    let is_number = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if (units.is_empty() && decimals.is_empty()) || !is_number(units) || !is_number(decimals) {
+        return None;
+    }
+
+    let units = if units.is_empty() {
+        0
+    } else {
+        units.parse::<i64>().ok()?
+    };
+    let decimals = format!("{:0<3}", decimals);
+    let cents = decimals[..2].parse::<i64>().ok()?;
+    let rounding = if decimals.as_bytes()[2] >= b'5' { 1 } else { 0 };
+
+    Some(sign * (units * 100 + cents + rounding))
+}
+
+/// Parses an OFX date (`YYYYMMDD[HHMMSS[.XXX][[gmt offset:tz name]]]`),
+/// keeping only the day.
+pub fn parse_date(value: &str) -> Option<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+/// OFX 1.x files are usually encoded in Windows-1252, which we approximate
+/// as Latin-1 when the file is not valid UTF-8.
+fn decode(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes)
+        .unwrap_or_else(|error| error.into_bytes().into_iter().map(char::from).collect())
+}
+
+fn create_transaction(
+    client: &Client,
+    statement_transaction: &StatementTransaction,
+    external_id: &str,
+    source_account: &Account,
+    destination_account: &Account,
+    payee: &Payee,
+) -> Result<Transaction, ImportOfxError> {
+    client
+        .create_transaction(&NewTransaction {
+            name: statement_transaction
+                .memo
+                .as_deref()
+                .unwrap_or(&statement_transaction.payee),
+            amount: statement_transaction.amount.abs(),
+            source_account_id: source_account.id,
+            destination_account_id: destination_account.id,
+            date: statement_transaction.date,
+            external_id: Some(external_id),
+            category_id: None,
+            budget_id: None,
+            notes: None,
+            payee_id: Some(payee.id),
+            time: None,
+            utc_offset: None,
+        })
+        .map_err(|e| e.into())
+}
@@ -0,0 +1,165 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("line {line}: {message}")]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+/// An OFX aggregate (with children) or element (with a value).
+///
+/// OFX 1.x files are SGML, where elements are never closed, while OFX 2.x
+/// files are XML. Both are parsed into the same tree.
+#[derive(Debug, Default)]
+pub struct Element {
+    /// Line of the opening tag.
+    pub line: usize,
+    pub name: String,
+    pub value: Option<String>,
+    pub children: Vec<Element>,
+}
+
+impl Element {
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// Returns the non-empty value of the first child named `name`.
+    pub fn child_value(&self, name: &str) -> Option<&str> {
+        self.child(name)
+            .and_then(|child| child.value.as_deref())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Returns every element named `name` below this one, in document order.
+    pub fn descendants<'a>(&'a self, name: &str) -> Vec<&'a Element> {
+        let mut descendants = Vec::new();
+        for child in &self.children {
+            if child.name == name {
+                descendants.push(child);
+            }
+            descendants.extend(child.descendants(name));
+        }
+
+        descendants
+    }
+}
+
+pub fn parse(input: &str) -> Result<Element, ParseError> {
+    let body_start = input.find("<OFX>").ok_or_else(|| ParseError {
+        line: 1,
+        message: "missing <OFX> root element".to_string(),
+    })?;
+    let mut line = 1 + input[..body_start].matches('\n').count();
+    let mut rest = &input[body_start..];
+
+    let mut stack = vec![Element::default()];
+
+    while !rest.is_empty() {
+        if let Some(tag_content) = rest.strip_prefix('<') {
+            let tag_end = tag_content.find('>').ok_or_else(|| ParseError {
+                line,
+                message: "unterminated tag".to_string(),
+            })?;
+            let tag = &tag_content[..tag_end];
+            line += tag.matches('\n').count();
+            rest = &tag_content[tag_end + 1..];
+
+            if tag.starts_with('?') || tag.starts_with('!') {
+                continue;
+            }
+
+            if let Some(name) = tag.strip_prefix('/') {
+                close_element(&mut stack, name.trim(), line)?;
+            } else {
+                close_leaf_element(&mut stack);
+
+                let is_empty = tag.ends_with('/');
+                let name = tag
+                    .trim_end_matches('/')
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or_default();
+                stack.push(Element {
+                    line,
+                    name: name.to_string(),
+                    ..Element::default()
+                });
+                if is_empty {
+                    pop_element(&mut stack);
+                }
+            }
+        } else {
+            let text_end = rest.find('<').unwrap_or(rest.len());
+            let text = &rest[..text_end];
+            line += text.matches('\n').count();
+            rest = &rest[text_end..];
+
+            let text = text.trim();
+            if !text.is_empty() {
+                let element = stack.last_mut().expect("root element");
+                element.value = Some(decode_entities(text));
+            }
+        }
+    }
+
+    while stack.len() > 1 {
+        pop_element(&mut stack);
+    }
+
+    stack
+        .pop()
+        .and_then(|root| root.children.into_iter().find(|child| child.name == "OFX"))
+        .ok_or_else(|| ParseError {
+            line,
+            message: "missing <OFX> root element".to_string(),
+        })
+}
+
+/// Closes the element on top of the stack if it already holds a value, as
+/// SGML elements are not closed explicitly.
+fn close_leaf_element(stack: &mut Vec<Element>) {
+    if stack.len() > 1 && stack.last().is_some_and(|element| element.value.is_some()) {
+        pop_element(stack);
+    }
+}
+
+fn close_element(stack: &mut Vec<Element>, name: &str, line: usize) -> Result<(), ParseError> {
+    if !stack[1..].iter().any(|element| element.name == name) {
+        return Err(ParseError {
+            line,
+            message: format!("unexpected closing tag </{}>", name),
+        });
+    }
+
+    while let Some(element_name) = pop_element(stack) {
+        if element_name == name {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn pop_element(stack: &mut Vec<Element>) -> Option<String> {
+    let element = stack.pop()?;
+    let name = element.name.clone();
+    stack
+        .last_mut()
+        .expect("root element")
+        .children
+        .push(element);
+
+    Some(name)
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
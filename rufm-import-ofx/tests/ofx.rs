@@ -0,0 +1,102 @@
+use rufm_core::{
+    models::accounts::{AccountType, NewAccount},
+    *,
+};
+use rufm_import_ofx::*;
+
+const STATEMENT_V1: &[u8] = include_bytes!("fixtures/statement-v1.ofx");
+const STATEMENT_V2: &[u8] = include_bytes!("fixtures/statement-v2.ofx");
+
+#[test]
+fn can_import_sgml_statement() {
+    let client = setup_account("checking").unwrap();
+
+    import_ofx(&client, STATEMENT_V1, "checking").unwrap();
+
+    assert_eq!(client.list_transactions().unwrap().len(), 3);
+    assert_eq!(get_balance(&client, "checking"), 194745);
+    assert_eq!(get_balance(&client, "GROCERY STORE"), 5255);
+    assert_eq!(get_balance(&client, "ACME CORP"), -200000);
+    assert_eq!(
+        client
+            .get_account_by_name("GROCERY STORE")
+            .unwrap()
+            .account_type,
+        AccountType::Expense
+    );
+    assert_eq!(
+        client
+            .get_account_by_name("ACME CORP")
+            .unwrap()
+            .account_type,
+        AccountType::Revenue
+    );
+}
+
+#[test]
+fn can_import_xml_statement() {
+    let client = setup_account("credit card").unwrap();
+
+    let report = import_ofx(&client, STATEMENT_V2, "credit card").unwrap();
+
+    // The refund would book on the Expense account of the bookshop.
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].line, Some(41));
+    assert_eq!(client.list_transactions().unwrap().len(), 2);
+    assert_eq!(get_balance(&client, "credit card"), -2749);
+    assert_eq!(get_balance(&client, "Bookshop"), 1999);
+    assert_eq!(get_balance(&client, "Café"), 750);
+}
+
+#[test]
+fn reimport_skips_already_imported_transactions() {
+    let client = setup_account("checking").unwrap();
+
+    import_ofx(&client, STATEMENT_V1, "checking").unwrap();
+    let report = import_ofx(&client, STATEMENT_V1, "checking").unwrap();
+
+    assert!(report.created_transactions.is_empty());
+    assert_eq!(report.skipped_duplicates.len(), 3);
+
+    assert_eq!(client.list_transactions().unwrap().len(), 3);
+    assert_eq!(get_balance(&client, "checking"), 194745);
+}
+
+#[test]
+fn import_fails_on_unknown_account() {
+    let client = setup_account("checking").unwrap();
+
+    let result = import_ofx(&client, STATEMENT_V1, "savings");
+
+    assert!(matches!(result, Err(ImportOfxError::AccountNotFound(_))));
+}
+
+#[test]
+fn can_parse_amounts() {
+    assert_eq!(parse_amount("-42.50"), Some(-4250));
+    assert_eq!(parse_amount("+5"), Some(500));
+    assert_eq!(parse_amount("12,3"), Some(1230));
+    assert_eq!(parse_amount(".99"), Some(99));
+    assert_eq!(parse_amount("1.005"), Some(101));
+    assert_eq!(parse_amount("abc"), None);
+    assert_eq!(parse_amount("-"), None);
+}
+
+// Helper functions
+
+fn setup_account(name: &str) -> Result<Client, Box<dyn std::error::Error>> {
+    let client = Client::new(None)?;
+    client.create_account(&NewAccount {
+        name,
+        account_type: AccountType::Asset,
+        initial_balance: 0,
+    })?;
+
+    Ok(client)
+}
+
+fn get_balance(client: &Client, account_name: &str) -> i64 {
+    let account = client.get_account_by_name(account_name).unwrap();
+
+    client.get_account_balance(&account.id).unwrap()
+}
@@ -0,0 +1,473 @@
+extern crate rufm_core;
+use rufm_core::{
+    import::{
+        get_or_create_payee_account,
+        AccountTypeMismatch,
+        ImportReport,
+        Importer,
+        ImporterRegistry,
+        PayeeAccountError,
+    },
+    models::{
+        accounts::{Account, AccountType, NewAccount},
+        payees::Payee,
+        transactions::{NewTransaction, Transaction},
+    },
+    AccountsRepository,
+    Client,
+    OptionalExtension,
+    PayeesRepository,
+    TransactionsRepository,
+};
+use thiserror::Error;
+
+mod parser;
+
+pub use parser::{ParseError, Record, Split};
+
+#[derive(Error, Debug)]
+pub enum ImportQifError {
+    #[error("io error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("qif parse error: {0}")]
+    ParseError(#[from] ParseError),
+    #[error("line {0}: missing {1}")]
+    MissingField(usize, &'static str),
+    #[error("line {0}: invalid amount: {1}")]
+    InvalidAmount(usize, String),
+    #[error("line {0}: invalid date: {1}")]
+    InvalidDate(usize, String),
+    #[error("account not found: {0}")]
+    AccountNotFound(String),
+    #[error("{0}")]
+    AccountTypeMismatch(#[from] AccountTypeMismatch),
+    #[error("database error: {0}")]
+    DatabaseError(#[from] rufm_core::QueryError),
+}
+
+impl From<PayeeAccountError> for ImportQifError {
+    fn from(error: PayeeAccountError) -> ImportQifError {
+        match error {
+            PayeeAccountError::AccountTypeMismatch(error) => error.into(),
+            PayeeAccountError::DatabaseError(error) => error.into(),
+        }
+    }
+}
+
+/// Order of the day and the month in dates, when no explicit date format is
+/// given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateOrder {
+    MonthDayYear,
+    DayMonthYear,
+}
+
+/// The Quicken Interchange Format.
+pub struct QifImporter;
+
+impl Importer for QifImporter {
+    fn name(&self) -> &'static str {
+        "qif"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["qif"]
+    }
+
+    fn sniff(&self, head: &[u8]) -> bool {
+        let head = String::from_utf8_lossy(head);
+        let head = head.trim_start_matches('\u{feff}').trim_start();
+
+        head.starts_with("!Type:") || head.starts_with("!Account") || head.starts_with("!Option:")
+    }
+
+    fn import(
+        &self,
+        client: &Client,
+        rdr: &mut dyn std::io::Read,
+        account: Option<&str>,
+    ) -> Result<ImportReport, Box<dyn std::error::Error>> {
+        let account = account.ok_or("an account name is required to import QIF files")?;
+
+        Ok(import_qif(client, rdr, account, None)?)
+    }
+}
+
+/// Adds the importers of this crate to `importers`.
+pub fn register(importers: &mut ImporterRegistry) {
+    importers.register(QifImporter);
+}
+
+/// Imports every transaction of a QIF file into `account_name`.
+///
+/// Dates are parsed with `date_format` (a `chrono` format string) when given,
+/// otherwise the day/month order is guessed from the whole file. Categories
+/// in brackets (`[Savings]`) are transfers to the asset account of that
+/// name, other counterparties are mapped to Expense (debits) or Revenue
+/// (credits) accounts named after their payee.
+pub fn import_qif<R: std::io::Read>(
+    client: &Client,
+    mut rdr: R,
+    account_name: &str,
+    date_format: Option<&str>,
+) -> Result<ImportReport, ImportQifError> {
+    let mut bytes = Vec::new();
+    rdr.read_to_end(&mut bytes)?;
+    let records = parser::parse(&decode(bytes))?;
+
+    let account = client
+        .get_account_by_name(account_name)
+        .optional()?
+        .ok_or_else(|| ImportQifError::AccountNotFound(account_name.to_string()))?;
+
+    let date_order = detect_date_order(records.iter().filter_map(|record| record.date.as_deref()));
+
+    let mut report = ImportReport::default();
+    for record in &records {
+        match handle_record(
+            client,
+            &account,
+            record,
+            date_format,
+            date_order,
+            &mut report,
+        ) {
+            Err(ImportQifError::DatabaseError(error)) => return Err(error.into()),
+            Err(error) => report.add_error(Some(record.line as u64), error),
+            Ok(()) => {}
+        }
+    }
+
+    Ok(report)
+}
+
+pub fn handle_record(
+    client: &Client,
+    account: &Account,
+    record: &Record,
+    date_format: Option<&str>,
+    date_order: DateOrder,
+    report: &mut ImportReport,
+) -> Result<(), ImportQifError> {
+    let date = record
+        .date
+        .as_deref()
+        .ok_or(ImportQifError::MissingField(record.line, "date"))?;
+    let date = parse_date(date, date_format, date_order)
+        .ok_or_else(|| ImportQifError::InvalidDate(record.line, date.to_string()))?;
+
+    if record.splits.is_empty() {
+        let amount = parse_record_amount(record.line, record.amount.as_deref())?;
+
+        if record.category.as_deref().and_then(transfer_account_name) == Some(&account.name) {
+            return handle_opening_balance(client, account, amount, report);
+        }
+
+        handle_transaction(
+            client,
+            account,
+            record,
+            &Posting {
+                category: record.category.as_deref(),
+                memo: record.memo.as_deref(),
+                amount,
+                date,
+            },
+            report,
+        )
+    } else {
+        let amounts = record
+            .splits
+            .iter()
+            .map(|split| parse_record_amount(record.line, split.amount.as_deref()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (split, amount) in record.splits.iter().zip(amounts) {
+            handle_transaction(
+                client,
+                account,
+                record,
+                &Posting {
+                    category: split.category.as_deref(),
+                    memo: split.memo.as_deref().or(record.memo.as_deref()),
+                    amount,
+                    date,
+                },
+                report,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Quicken records the opening balance as a transfer from the account to
+/// itself.
+fn handle_opening_balance(
+    client: &Client,
+    account: &Account,
+    amount: i64,
+    report: &mut ImportReport,
+) -> Result<(), ImportQifError> {
+    let new_account = client.update_account_initial_balance(&Account {
+        id: account.id,
+        name: account.name.clone(),
+        account_type: account.account_type,
+        initial_balance: amount,
+    })?;
+    report.add_initial_balance(&new_account);
+
+    Ok(())
+}
+
+/// The transaction of a record, or one of its splits.
+struct Posting<'a> {
+    category: Option<&'a str>,
+    memo: Option<&'a str>,
+    amount: i64,
+    date: chrono::NaiveDate,
+}
+
+fn handle_transaction(
+    client: &Client,
+    account: &Account,
+    record: &Record,
+    posting: &Posting,
+    report: &mut ImportReport,
+) -> Result<(), ImportQifError> {
+    let Posting {
+        category,
+        memo,
+        amount,
+        date,
+    } = *posting;
+    let payee = match (&record.payee, category.and_then(transfer_account_name)) {
+        (Some(payee), None) => Some(client.get_or_create_payee(payee)?),
+        _ => None,
+    };
+    let counterparty_account =
+        get_counterparty_account(client, report, payee.as_ref(), category, amount)?;
+    let (source_account, destination_account) = if amount < 0 {
+        (account, &counterparty_account)
+    } else {
+        (&counterparty_account, account)
+    };
+
+    let transaction = create_transaction(
+        client,
+        memo.or(record.payee.as_deref())
+            .unwrap_or(&counterparty_account.name),
+        amount.abs(),
+        date,
+        source_account,
+        destination_account,
+        payee.as_ref(),
+    )?;
+    report.add_transaction(&transaction, source_account, destination_account);
+
+    Ok(())
+}
+
+fn parse_record_amount(line: usize, amount: Option<&str>) -> Result<i64, ImportQifError> {
+    let amount = amount.ok_or(ImportQifError::MissingField(line, "amount"))?;
+
+    parse_amount(amount).ok_or_else(|| ImportQifError::InvalidAmount(line, amount.to_string()))
+}
+
+/// Parses a QIF amount into cents, whether it uses `1,234.56` or
+/// `1.234,56` separators. A single separator followed by exactly three
+/// digits, as in `1.234` or `1,000`, separates thousands.
+pub fn parse_amount(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (sign, digits) = match value.strip_prefix('-') {
+        Some(digits) => (-1, digits),
+        None => (1, value.strip_prefix('+').unwrap_or(value)),
+    };
+
+    let decimal_separator = match (digits.rfind('.'), digits.rfind(',')) {
+        (Some(dot), Some(comma)) => Some(dot.max(comma)),
+        (Some(dot), None) if digits.len() - dot != 4 => Some(dot),
+        (None, Some(comma)) if digits.len() - comma <= 3 => Some(comma),
+        _ => None,
+    };
+    let (units, decimals) = match decimal_separator {
+        Some(index) => (&digits[..index], &digits[index + 1..]),
+        None => (digits, ""),
+    };
+    let units = units.replace([',', '.', '\''], "");
+
+    let is_number = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if (units.is_empty() && decimals.is_empty())
+        || !is_number(&units)
+        || !is_number(decimals)
+        || decimals.len() > 2
+    {
+        return None;
+    }
+
+    let units = if units.is_empty() {
+        0
+    } else {
+        units.parse::<i64>().ok()?
+    };
+    let cents = format!("{:0<2}", decimals).parse::<i64>().ok()?;
+
+    Some(sign * (units * 100 + cents))
+}
+
+/// Parses a QIF date.
+///
+/// Without an explicit `date_format`, dates such as `1/ 5'21`, `01/05/2021`,
+/// `05.01.2021` or `2021-01-05` are accepted. Following Quicken, two-digit
+/// years after an apostrophe are in the 2000s; other two-digit years are
+/// pivoted around 1970.
+pub fn parse_date(
+    value: &str,
+    date_format: Option<&str>,
+    date_order: DateOrder,
+) -> Option<chrono::NaiveDate> {
+    let value = value.replace(' ', "");
+
+    if let Some(date_format) = date_format {
+        return chrono::NaiveDate::parse_from_str(&value, date_format).ok();
+    }
+
+    let parts = value
+        .split(['/', '.', '-', '\''])
+        .map(|part| part.parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let (first, second, year) = match parts.as_slice() {
+        [year, month, day] if *year > 999 => return ymd(*year as i32, *month, *day),
+        [first, second, year] => (*first, *second, *year),
+        _ => return None,
+    };
+
+    let year = match year {
+        0..=99 if value.contains('\'') => 2000 + year,
+        0..=69 => 2000 + year,
+        70..=99 => 1900 + year,
+        _ => year,
+    } as i32;
+
+    match date_order {
+        DateOrder::MonthDayYear => ymd(year, first, second),
+        DateOrder::DayMonthYear => ymd(year, second, first),
+    }
+}
+
+/// Dates are read as day/month when at least one of them could not be a
+/// month/day, or when they use dots, and as month/day otherwise.
+pub fn detect_date_order<'a, I: Iterator<Item = &'a str>>(dates: I) -> DateOrder {
+    for date in dates {
+        let first = date
+            .trim()
+            .split(['/', '.', '-', '\''])
+            .next()
+            .and_then(|part| part.trim().parse::<u32>().ok());
+
+        if date.contains('.') || first.is_some_and(|first| (13..=31).contains(&first)) {
+            return DateOrder::DayMonthYear;
+        }
+    }
+
+    DateOrder::MonthDayYear
+}
+
+fn ymd(year: i32, month: u32, day: u32) -> Option<chrono::NaiveDate> {
+    chrono::NaiveDate::from_ymd_opt(year, month, day)
+}
+
+/// Returns the account name of a transfer category (`[Savings]`).
+fn transfer_account_name(category: &str) -> Option<&str> {
+    let name = category.strip_prefix('[')?;
+
+    name.find(']').map(|end| &name[..end])
+}
+
+/// QIF files are usually encoded in Windows-1252, which we approximate as
+/// Latin-1 when the file is not valid UTF-8.
+fn decode(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes)
+        .unwrap_or_else(|error| error.into_bytes().into_iter().map(char::from).collect())
+}
+
+/// The account of a transfer category, or else the account of the payee, or
+/// else an account named after the category.
+fn get_counterparty_account(
+    client: &Client,
+    report: &mut ImportReport,
+    payee: Option<&Payee>,
+    category: Option<&str>,
+    amount: i64,
+) -> Result<Account, ImportQifError> {
+    if let Some(account_name) = category.and_then(transfer_account_name) {
+        return get_or_create_account(client, report, account_name, AccountType::Asset);
+    }
+    let account_type = if amount < 0 {
+        AccountType::Expense
+    } else {
+        AccountType::Revenue
+    };
+    match payee {
+        Some(payee) => Ok(get_or_create_payee_account(
+            client,
+            report,
+            payee,
+            account_type,
+        )?),
+        None => get_or_create_account(
+            client,
+            report,
+            category.unwrap_or("Unknown payee"),
+            account_type,
+        ),
+    }
+}
+
+fn get_or_create_account(
+    client: &Client,
+    report: &mut ImportReport,
+    account_name: &str,
+    account_type: AccountType,
+) -> Result<Account, ImportQifError> {
+    match client.get_account_by_name(account_name).optional()? {
+        Some(account) => Ok(account),
+        None => {
+            let account = client.create_account(&NewAccount {
+                name: account_name,
+                account_type,
+                initial_balance: 0,
+            })?;
+            report.add_account(&account);
+
+            Ok(account)
+        }
+    }
+}
+
+fn create_transaction(
+    client: &Client,
+    name: &str,
+    amount: i64,
+    date: chrono::NaiveDate,
+    source_account: &Account,
+    destination_account: &Account,
+    payee: Option<&Payee>,
+) -> Result<Transaction, ImportQifError> {
+    client
+        .create_transaction(&NewTransaction {
+            name,
+            amount,
+            source_account_id: source_account.id,
+            destination_account_id: destination_account.id,
+            date,
+            external_id: None,
+            category_id: None,
+            budget_id: None,
+            notes: None,
+            payee_id: payee.map(|payee| payee.id),
+            time: None,
+            utc_offset: None,
+        })
+        .map_err(|e| e.into())
+}
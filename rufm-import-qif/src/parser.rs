@@ -0,0 +1,104 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("line {line}: {message}")]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+/// A transaction of a `!Type:Bank`, `!Type:CCard` or `!Type:Cash` section,
+/// with its fields left unparsed.
+#[derive(Debug, Default)]
+pub struct Record {
+    pub line: usize,
+    pub date: Option<String>,
+    pub amount: Option<String>,
+    pub payee: Option<String>,
+    pub memo: Option<String>,
+    pub category: Option<String>,
+    pub splits: Vec<Split>,
+}
+
+#[derive(Debug, Default)]
+pub struct Split {
+    pub category: Option<String>,
+    pub memo: Option<String>,
+    pub amount: Option<String>,
+}
+
+const SUPPORTED_TYPES: &[&str] = &["Bank", "CCard", "Cash"];
+
+/// Parses the transactions of every supported section, skipping the other
+/// sections (accounts list, investments, categories, ...).
+pub fn parse(input: &str) -> Result<Vec<Record>, ParseError> {
+    let mut records = Vec::new();
+    let mut section: Option<bool> = None;
+    let mut record = Record::default();
+
+    for (index, line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('!') {
+            if let Some(account_type) = header.strip_prefix("Type:") {
+                section = Some(SUPPORTED_TYPES.contains(&account_type.trim()));
+            } else if header.starts_with("Account") {
+                section = Some(false);
+            }
+            continue;
+        }
+
+        match section {
+            None => {
+                return Err(ParseError {
+                    line: line_number,
+                    message: "missing !Type header".to_string(),
+                })
+            }
+            Some(false) => continue,
+            Some(true) => (),
+        }
+
+        if record.line == 0 {
+            record.line = line_number;
+        }
+
+        let code = line.chars().next().expect("non-empty line");
+        let value = line[code.len_utf8()..].trim();
+        let value = Some(value.to_string()).filter(|value| !value.is_empty());
+        match code {
+            'D' => record.date = value,
+            'T' => record.amount = value,
+            'U' => record.amount = record.amount.take().or(value),
+            'P' => record.payee = value,
+            'M' => record.memo = value,
+            'L' => record.category = value,
+            'S' => record.splits.push(Split {
+                category: value,
+                ..Split::default()
+            }),
+            'E' => last_split(&mut record).memo = value,
+            '$' => last_split(&mut record).amount = value,
+            '^' => records.push(std::mem::take(&mut record)),
+            _ => (),
+        }
+    }
+
+    if record.line != 0 {
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+fn last_split(record: &mut Record) -> &mut Split {
+    if record.splits.is_empty() {
+        record.splits.push(Split::default());
+    }
+
+    record.splits.last_mut().expect("split")
+}
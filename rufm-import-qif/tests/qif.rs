@@ -0,0 +1,166 @@
+use chrono::NaiveDate;
+use rufm_core::{
+    models::accounts::{AccountType, NewAccount},
+    *,
+};
+use rufm_import_qif::*;
+
+const BANK: &[u8] = include_bytes!("fixtures/bank.qif");
+const CREDIT_CARD: &[u8] = include_bytes!("fixtures/ccard.qif");
+const AMBIGUOUS: &[u8] = include_bytes!("fixtures/ambiguous.qif");
+
+#[test]
+fn can_import_bank_account() {
+    let client = setup_account("Checking").unwrap();
+
+    let report = import_qif(&client, BANK, "Checking", None).unwrap();
+
+    assert_eq!(report.created_transactions.len(), 5);
+    assert_eq!(report.initial_balances[0].initial_balance, 100000);
+    assert_eq!(client.list_transactions().unwrap().len(), 5);
+    assert_eq!(
+        client
+            .get_account_by_name("Checking")
+            .unwrap()
+            .initial_balance,
+        100000
+    );
+    assert_eq!(get_balance(&client, "Checking"), 265433);
+    assert_eq!(get_balance(&client, "Supermarket"), 4567);
+    assert_eq!(get_balance(&client, "ACME Corp"), -250000);
+    assert_eq!(get_balance(&client, "Home Depot"), 30000);
+    assert_eq!(get_balance(&client, "Savings"), 50000);
+    assert_eq!(
+        client.get_account_by_name("Savings").unwrap().account_type,
+        AccountType::Asset
+    );
+}
+
+#[test]
+fn splits_create_one_transaction_each() {
+    let client = setup_account("Checking").unwrap();
+
+    import_qif(&client, BANK, "Checking", None).unwrap();
+
+    let mut split_names = client
+        .list_transactions()
+        .unwrap()
+        .into_iter()
+        .filter(|transaction| transaction.date == ymd(2021, 1, 20))
+        .map(|transaction| (transaction.name, transaction.amount))
+        .collect::<Vec<_>>();
+    split_names.sort();
+
+    assert_eq!(
+        split_names,
+        vec![("Drill".to_string(), 25000), ("Snacks".to_string(), 5000)]
+    );
+}
+
+#[test]
+fn can_import_credit_card_account() {
+    let client = setup_account("Visa").unwrap();
+
+    import_qif(&client, CREDIT_CARD, "Visa", None).unwrap();
+
+    assert_eq!(client.list_transactions().unwrap().len(), 2);
+    assert_eq!(get_balance(&client, "Visa"), -102499);
+}
+
+#[test]
+fn ambiguous_dates_default_to_month_first() {
+    let client = setup_account("Checking").unwrap();
+
+    import_qif(&client, AMBIGUOUS, "Checking", None).unwrap();
+
+    assert_eq!(client.list_transactions().unwrap()[0].date, ymd(2021, 3, 4));
+}
+
+#[test]
+fn date_format_overrides_date_detection() {
+    let client = setup_account("Checking").unwrap();
+
+    import_qif(&client, AMBIGUOUS, "Checking", Some("%d/%m/%Y")).unwrap();
+
+    assert_eq!(client.list_transactions().unwrap()[0].date, ymd(2021, 4, 3));
+}
+
+#[test]
+fn can_parse_dates() {
+    let month_first = DateOrder::MonthDayYear;
+    let day_first = DateOrder::DayMonthYear;
+
+    assert_eq!(
+        parse_date("1/ 5'21", None, month_first),
+        Some(ymd(2021, 1, 5))
+    );
+    assert_eq!(
+        parse_date("12/25/99", None, month_first),
+        Some(ymd(1999, 12, 25))
+    );
+    assert_eq!(
+        parse_date("12/25/2020", None, month_first),
+        Some(ymd(2020, 12, 25))
+    );
+    assert_eq!(
+        parse_date("25.12.2020", None, day_first),
+        Some(ymd(2020, 12, 25))
+    );
+    assert_eq!(
+        parse_date("2020-12-25", None, day_first),
+        Some(ymd(2020, 12, 25))
+    );
+    assert_eq!(parse_date("25/12/2020", None, month_first), None);
+    assert_eq!(
+        detect_date_order(vec!["01/02/2021", "25/02/2021"].into_iter()),
+        day_first
+    );
+    assert_eq!(
+        detect_date_order(vec!["01/02/2021"].into_iter()),
+        month_first
+    );
+}
+
+#[test]
+fn can_parse_amounts() {
+    assert_eq!(parse_amount("-1,234.56"), Some(-123456));
+    assert_eq!(parse_amount("-1.234,56"), Some(-123456));
+    assert_eq!(parse_amount("19,99"), Some(1999));
+    assert_eq!(parse_amount("1,000"), Some(100000));
+    assert_eq!(parse_amount("42"), Some(4200));
+    assert_eq!(parse_amount("1.234"), Some(123400));
+    assert_eq!(parse_amount("-1.234.567"), Some(-123456700));
+    assert_eq!(parse_amount("1.2345"), None);
+}
+
+#[test]
+fn import_fails_on_unknown_account() {
+    let client = setup_account("Checking").unwrap();
+
+    let result = import_qif(&client, BANK, "Savings", None);
+
+    assert!(matches!(result, Err(ImportQifError::AccountNotFound(_))));
+}
+
+// Helper functions
+
+fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn setup_account(name: &str) -> Result<Client, Box<dyn std::error::Error>> {
+    let client = Client::new(None)?;
+    client.create_account(&NewAccount {
+        name,
+        account_type: AccountType::Asset,
+        initial_balance: 0,
+    })?;
+
+    Ok(client)
+}
+
+fn get_balance(client: &Client, account_name: &str) -> i64 {
+    let account = client.get_account_by_name(account_name).unwrap();
+
+    client.get_account_balance(&account.id).unwrap()
+}